@@ -1,4 +1,4 @@
-pub(crate) const POKE_API_BASE_URL: &'static str = "https://pokeapi.co/api";
+pub(crate) const POKE_API_BASE_URL: &str = "https://pokeapi.co/api";
 
 pub(crate) static POKE_API_CLIENT_NAME: &str = concat!(
     "rs-",
@@ -77,21 +77,34 @@ decl_resource_list!(BerryFirmnessResourceList for BerryFirmness with BerryFirmne
 decl_named_resource_list!(BerryFirmnessNamedResourceList for BerryFirmness with BerryFirmnessNamedResource);
 
 #[cfg(test)]
-#[allow(dead_code)]
+#[allow(dead_code, non_fmt_panics, clippy::bool_assert_comparison)]
 mod test {
     use crate::api::v2::berry::{
         BerryEndpoint, BerryFirmnessEndpoint, BerryFirmnessNamedResource,
         BerryFirmnessNamedResourceList, BerryFirmnessResource, BerryNamedResource,
         BerryNamedResourceList, BerryResource,
     };
+    use crate::api::v2::cassette::Cassette;
     use crate::api::v2::client::ApiClient;
     use crate::api::v2::endpoint::ApiEndpoint;
+    use crate::api::v2::key::ResourceKey;
     use crate::api::v2::resource::{ApiNamedResourceList, ApiResource};
     use crate::models::v2::resource::{NamedResource, NamedResourceList, Resource};
 
+    /// Client replaying responses recorded from the live API.
+    fn client() -> ApiClient {
+        let cassette = Cassette::replay(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/berry.json"
+        ))
+        .unwrap();
+
+        ApiClient::new().unwrap().with_cassette(cassette)
+    }
+
     #[tokio::test]
     async fn berry_resource() {
-        let client = ApiClient::new().unwrap();
+        let client = client();
         let resource = Resource {
             url: "https://pokeapi.co/api/v2/berry/1".into(),
         };
@@ -109,7 +122,7 @@ mod test {
 
     #[tokio::test]
     async fn berry_named_resource() {
-        let client = ApiClient::new().unwrap();
+        let client = client();
         let named_resource = NamedResource {
             name: "cheri".into(),
             url: "https://pokeapi.co/api/v2/berry/1".into(),
@@ -128,7 +141,7 @@ mod test {
 
     #[tokio::test]
     async fn berry_named_resource_list() {
-        let client = ApiClient::new().unwrap();
+        let client = client();
 
         let resource_list = NamedResourceList {
             count: 64,
@@ -184,7 +197,7 @@ mod test {
 
     #[tokio::test]
     async fn berry_api_get_by_id() {
-        let berry_api = BerryEndpoint::new(ApiClient::new().unwrap());
+        let berry_api = BerryEndpoint::new(client());
 
         assert_eq!(BerryEndpoint::name(), "berry");
 
@@ -213,7 +226,7 @@ mod test {
 
     #[tokio::test]
     async fn berry_api_get_by_name() {
-        let berry_api = BerryEndpoint::new(ApiClient::new().unwrap());
+        let berry_api = BerryEndpoint::new(client());
 
         assert_eq!(BerryEndpoint::name(), "berry");
 
@@ -240,9 +253,33 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn berry_api_get_by_key() {
+        let berry_api = BerryEndpoint::new(client());
+
+        {
+            let key = " Cheri ".parse::<ResourceKey>().unwrap();
+            let cheri = berry_api.get(key).await;
+            assert!(cheri.is_ok());
+
+            let cheri = cheri.unwrap();
+            assert_eq!(cheri.name, "cheri");
+            assert_eq!(cheri.id, 1);
+        }
+
+        {
+            let aspear = berry_api.get(5).await;
+            assert!(aspear.is_ok());
+
+            let aspear = aspear.unwrap();
+            assert_eq!(aspear.name, "aspear");
+            assert_eq!(aspear.id, 5);
+        }
+    }
+
     #[tokio::test]
     async fn berry_firmness_resource() {
-        let client = ApiClient::new().unwrap();
+        let client = client();
         let resource = Resource {
             url: "https://pokeapi.co/api/v2/berry-firmness/1".into(),
         };
@@ -259,7 +296,7 @@ mod test {
 
     #[tokio::test]
     async fn berry_firmness_named_resource() {
-        let client = ApiClient::new().unwrap();
+        let client = client();
         let resource = NamedResource {
             name: "very-soft".to_string(),
             url: "https://pokeapi.co/api/v2/berry-firmness/1".to_string(),
@@ -278,7 +315,7 @@ mod test {
 
     #[tokio::test]
     async fn berry_firmness_named_resource_list() {
-        let client = ApiClient::new().unwrap();
+        let client = client();
 
         let resource_list = NamedResourceList {
             count: 5,
@@ -337,7 +374,7 @@ mod test {
 
     #[tokio::test]
     async fn firmness_api_get_by_id() {
-        let firmness_api = BerryFirmnessEndpoint::new(ApiClient::new().unwrap());
+        let firmness_api = BerryFirmnessEndpoint::new(client());
 
         assert_eq!(BerryFirmnessEndpoint::name(), "berry-firmness");
 
@@ -362,7 +399,7 @@ mod test {
 
     #[tokio::test]
     async fn firmness_api_get_by_name() {
-        let firmness_api = BerryFirmnessEndpoint::new(ApiClient::new().unwrap());
+        let firmness_api = BerryFirmnessEndpoint::new(client());
 
         assert_eq!(BerryFirmnessEndpoint::name(), "berry-firmness");
 
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Storage for API responses.
+/// Responses are keyed by the full request url and stored as raw JSON bodies.
+/// Caching is best-effort: a backend failing to store a response must not fail the request.
+pub trait Cache: Send + Sync {
+    /// Gets the cached body of the response for the url, if any.
+    fn get(&self, url: &str) -> Option<String>;
+
+    /// Stores the body of the response for the url.
+    fn put(&self, url: &str, body: &str);
+}
+
+impl<C: Cache + ?Sized> Cache for Arc<C> {
+    fn get(&self, url: &str) -> Option<String> {
+        (**self).get(url)
+    }
+
+    fn put(&self, url: &str, body: &str) {
+        (**self).put(url, body)
+    }
+}
+
+impl<C: Cache + ?Sized> Cache for &C {
+    fn get(&self, url: &str) -> Option<String> {
+        (**self).get(url)
+    }
+
+    fn put(&self, url: &str, body: &str) {
+        (**self).put(url, body)
+    }
+}
+
+/// Cache keeping responses in memory for the lifetime of the process.
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryCache {
+    /// Creates empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cached responses.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Cache for MemoryCache {
+    fn get(&self, url: &str) -> Option<String> {
+        self.entries.lock().unwrap().get(url).cloned()
+    }
+
+    fn put(&self, url: &str, body: &str) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(url.to_string(), body.to_string());
+    }
+}
+
+/// Cache storing every response as a separate file in the directory.
+/// Survives between runs, which makes it suitable for command line tools.
+#[derive(Clone, Debug)]
+pub struct FileCache {
+    dir: PathBuf,
+}
+
+impl FileCache {
+    /// Creates cache in the given directory. The directory is created on the first write.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Path of the file storing the response for the url.
+    /// Every character except ASCII alphanumerics and `-` is escaped, so distinct urls never collide.
+    fn path(&self, url: &str) -> PathBuf {
+        let mut name = String::with_capacity(url.len() + 5);
+        for byte in url.bytes() {
+            match byte {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' => name.push(byte as char),
+                _ => name.push_str(&format!("_{:02x}", byte)),
+            }
+        }
+        name.push_str(".json");
+
+        self.dir.join(name)
+    }
+}
+
+impl Cache for FileCache {
+    fn get(&self, url: &str) -> Option<String> {
+        fs::read_to_string(self.path(url)).ok()
+    }
+
+    fn put(&self, url: &str, body: &str) {
+        if fs::create_dir_all(&self.dir).is_ok() {
+            let _ = fs::write(self.path(url), body);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Cache, FileCache, MemoryCache};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn memory_cache() {
+        let cache = MemoryCache::new();
+        assert!(cache.get("https://pokeapi.co/api/v2/berry/1").is_none());
+
+        cache.put("https://pokeapi.co/api/v2/berry/1", "{\"id\":1}");
+        assert_eq!(
+            cache.get("https://pokeapi.co/api/v2/berry/1"),
+            Some("{\"id\":1}".to_string())
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn file_cache() {
+        let dir = env::temp_dir().join(format!("pokemon-api-file-cache-{}", std::process::id()));
+        let cache = FileCache::new(&dir);
+
+        cache.put("https://pokeapi.co/api/v2/berry/1", "{\"id\":1}");
+        cache.put("https://pokeapi.co/api/v2/berry_1", "{\"id\":2}");
+
+        assert_eq!(
+            cache.get("https://pokeapi.co/api/v2/berry/1"),
+            Some("{\"id\":1}".to_string())
+        );
+        assert_eq!(
+            cache.get("https://pokeapi.co/api/v2/berry_1"),
+            Some("{\"id\":2}".to_string())
+        );
+        assert!(cache.get("https://pokeapi.co/api/v2/berry/2").is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
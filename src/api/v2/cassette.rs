@@ -0,0 +1,194 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Error, Result};
+
+/// Request and the response body recorded in a cassette.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Interaction {
+    /// Requested url.
+    pub url: String,
+
+    /// HTTP status of the response, `200` when not recorded.
+    #[serde(default = "ok_status")]
+    pub status: u16,
+
+    /// Body of the response.
+    pub response: Value,
+}
+
+fn ok_status() -> u16 {
+    200
+}
+
+/// Mode the cassette is used in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CassetteMode {
+    /// Requests are sent to the API and every response is written to the cassette file.
+    Record,
+
+    /// Responses are read from the cassette file, requests never reach the network.
+    Replay,
+}
+
+/// Record-and-replay storage of API interactions.
+///
+/// In the record mode the client saves every response to the cassette file, error statuses
+/// included. In the replay mode the client answers requests from the file only, fails with
+/// `Error::StatusError` on responses recorded with an error status and with
+/// `Error::UnrecordedRequest` on any request that was not recorded.
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl Cassette {
+    /// Creates empty cassette recording into the file.
+    /// The file is overwritten with the first recorded interaction.
+    pub fn record<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            mode: CassetteMode::Record,
+            interactions: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Loads cassette from the file to replay recorded interactions.
+    pub fn replay<P: Into<PathBuf>>(path: P) -> Result<Self> {
+        let path = path.into();
+        let interactions = serde_json::from_str(&fs::read_to_string(&path)?)?;
+
+        Ok(Self {
+            path,
+            mode: CassetteMode::Replay,
+            interactions: Mutex::new(interactions),
+        })
+    }
+
+    /// Mode of the cassette.
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Recorded interactions.
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.interactions.lock().unwrap().clone()
+    }
+
+    /// Finds the body of the response recorded for the url.
+    pub(crate) fn play(&self, url: &str) -> Result<String> {
+        let interactions = self.interactions.lock().unwrap();
+        let interaction = interactions
+            .iter()
+            .find(|interaction| interaction.url == url)
+            .ok_or_else(|| Error::UnrecordedRequest(url.to_string()))?;
+
+        if interaction.status >= 400 {
+            return Err(Error::StatusError(interaction.status, url.to_string()));
+        }
+
+        Ok(match interaction.response {
+            Value::String(ref body) => body.clone(),
+            ref body => body.to_string(),
+        })
+    }
+
+    /// Adds the interaction and writes the whole cassette to the file.
+    /// Bodies are stored as JSON documents, so cassettes stay readable and diffable.
+    pub(crate) fn save(&self, url: &str, status: u16, body: &str) -> Result<()> {
+        let mut interactions = self.interactions.lock().unwrap();
+        if interactions
+            .iter()
+            .any(|interaction| interaction.url == url)
+        {
+            return Ok(());
+        }
+
+        interactions.push(Interaction {
+            url: url.to_string(),
+            status,
+            response: serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.into())),
+        });
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_vec_pretty(&*interactions)?)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Cassette, CassetteMode};
+    use crate::api::v2::client::ApiClient;
+    use crate::api::v2::endpoint::ApiEndpoint;
+    use crate::testing::MockServer;
+    use crate::Error;
+    use serde_json::json;
+    use std::env;
+    use std::fs;
+
+    #[tokio::test]
+    async fn records_and_replays() {
+        let path =
+            env::temp_dir().join(format!("pokemon-api-cassette-{}.json", std::process::id()));
+
+        let server = MockServer::start().unwrap();
+        server.add_resource(
+            "berry-firmness",
+            json!({ "id": 2, "name": "soft", "berries": [], "names": [] }),
+        );
+
+        {
+            let client = server
+                .client()
+                .unwrap()
+                .with_cassette(Cassette::record(&path));
+            let soft = client.berry_firmnesses().get_by_id(2).await.unwrap();
+            assert_eq!(soft.name, "soft");
+            assert!(client.berry_firmnesses().get_by_id(4).await.is_err());
+        }
+
+        let cassette = Cassette::replay(&path).unwrap();
+        assert_eq!(cassette.mode(), CassetteMode::Replay);
+        let statuses: Vec<u16> = cassette.interactions().iter().map(|i| i.status).collect();
+        assert_eq!(statuses, vec![200, 404]);
+
+        let base_url = server.base_url();
+        drop(server);
+
+        let client = ApiClient::new()
+            .unwrap()
+            .with_base_url(base_url)
+            .with_cassette(cassette);
+
+        let soft = client.berry_firmnesses().get_by_id(2).await.unwrap();
+        assert_eq!(soft.name, "soft");
+
+        match client.berry_firmnesses().get_by_id(4).await {
+            Err(Error::StatusError(404, url)) => assert!(url.ends_with("/v2/berry-firmness/4")),
+            other => panic!(
+                "unexpected result: {:?}",
+                other.map(|firmness| firmness.name)
+            ),
+        }
+
+        match client.berry_firmnesses().get_by_id(3).await {
+            Err(Error::UnrecordedRequest(url)) => assert!(url.ends_with("/v2/berry-firmness/3")),
+            other => panic!(
+                "unexpected result: {:?}",
+                other.map(|firmness| firmness.name)
+            ),
+        }
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -1,14 +1,29 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
 use reqwest::Client as ReqClient;
 use reqwest::ClientBuilder;
 use reqwest::Response;
 
-use crate::api::v2::berry::BerryEndpoint;
+use crate::api::v2::berry::{BerryEndpoint, BerryFirmnessEndpoint};
+use crate::api::v2::cache::Cache;
+use crate::api::v2::cassette::{Cassette, CassetteMode};
+use crate::api::v2::limiter::RateLimiter;
+use crate::api::v2::moves::MoveEndpoint;
+use crate::api::v2::pokemon::{
+    EggGroupEndpoint, NatureEndpoint, PokemonEndpoint, PokemonSpeciesEndpoint, TypeEndpoint,
+};
+use crate::api::v2::raw::WithRaw;
+use crate::api::v2::retry::RetryPolicy;
+use crate::api::v2::schema::{self, DecodeMode, DriftReport};
+use crate::api::v2::search::SearchIndex;
 use crate::api::POKE_API_BASE_URL;
 use crate::api::POKE_API_CLIENT_NAME;
 use crate::Result;
 
 /// API version path
-const POKEMON_API_V2: &'static str = "v2";
+const POKEMON_API_V2: &str = "v2";
 
 /// Client for version 2 of PokeAPI
 #[derive(Clone)]
@@ -18,6 +33,27 @@ pub struct ApiClient {
 
     /// Base url for requesting API resources
     url: String,
+
+    /// Optional rate limiter shared by all clones of the client
+    limiter: Option<RateLimiter>,
+
+    /// Optional policy of repeating requests failed with transient errors
+    retry: Option<RetryPolicy>,
+
+    /// Optional cache of responses shared by all clones of the client
+    cache: Option<Arc<dyn Cache>>,
+
+    /// Optional cassette recording or replaying responses
+    cassette: Option<Arc<Cassette>>,
+
+    /// Search indexes of endpoints built so far, shared by all clones of the client
+    indexes: Arc<Mutex<HashMap<String, Arc<SearchIndex>>>>,
+
+    /// How responses are turned into models
+    decode_mode: DecodeMode,
+
+    /// Differences between responses and models seen so far, shared by all clones of the client
+    drift: Arc<Mutex<DriftReport>>,
 }
 
 impl ApiClient {
@@ -27,25 +63,210 @@ impl ApiClient {
 
         Ok(ApiClient {
             client: builder.build()?,
-            url: POKE_API_BASE_URL.to_string() + "/".into() + POKEMON_API_V2.into(),
+            url: POKE_API_BASE_URL.to_string() + "/" + POKEMON_API_V2,
+            limiter: None,
+            retry: None,
+            cache: None,
+            cassette: None,
+            indexes: Arc::new(Mutex::new(HashMap::new())),
+            decode_mode: DecodeMode::default(),
+            drift: Arc::new(Mutex::new(DriftReport::default())),
         })
     }
 
+    /// Uses the API hosted at the given url instead of the public one.
+    /// The url is the API root without version, like `https://pokeapi.co/api`.
+    pub fn with_base_url<T: Into<String>>(mut self, url: T) -> Self {
+        self.url = url.into().trim_end_matches('/').to_string() + "/" + POKEMON_API_V2;
+        self
+    }
+
+    /// Limits the client to one request per `interval`.
+    /// PokeAPI is a free service, please be polite when crawling big parts of it.
+    pub fn with_rate_limit(mut self, interval: Duration) -> Self {
+        self.limiter = Some(RateLimiter::new(interval));
+        self
+    }
+
+    /// Repeats requests failed with transient errors according to the policy.
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Caches responses in the given storage.
+    /// Cached responses never expire, PokeAPI data is mostly static.
+    pub fn with_cache<C: Cache + 'static>(mut self, cache: C) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Records responses to the cassette or replays them from it, depending on its mode.
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(Arc::new(cassette));
+        self
+    }
+
+    /// Decodes responses in the given mode.
+    /// In the strict and lenient modes fields not matching the models are collected
+    /// in the drift report.
+    pub fn with_decode_mode(mut self, mode: DecodeMode) -> Self {
+        self.decode_mode = mode;
+        self
+    }
+
+    /// Fields of the responses not matching the models, collected so far.
+    pub fn drift_report(&self) -> DriftReport {
+        self.drift.lock().unwrap().clone()
+    }
+
     /// Access to berries API enpoint
     pub fn berries(&self) -> BerryEndpoint {
         BerryEndpoint::new(self.clone())
     }
+
+    /// Access to berry firmnesses API endpoint
+    pub fn berry_firmnesses(&self) -> BerryFirmnessEndpoint {
+        BerryFirmnessEndpoint::new(self.clone())
+    }
+
+    /// Access to types API endpoint
+    pub fn types(&self) -> TypeEndpoint {
+        TypeEndpoint::new(self.clone())
+    }
+
+    /// Access to Pokémon API endpoint
+    pub fn pokemon(&self) -> PokemonEndpoint {
+        PokemonEndpoint::new(self.clone())
+    }
+
+    /// Access to natures API endpoint
+    pub fn natures(&self) -> NatureEndpoint {
+        NatureEndpoint::new(self.clone())
+    }
+
+    /// Access to Pokémon species API endpoint
+    pub fn pokemon_species(&self) -> PokemonSpeciesEndpoint {
+        PokemonSpeciesEndpoint::new(self.clone())
+    }
+
+    /// Access to egg groups API endpoint
+    pub fn egg_groups(&self) -> EggGroupEndpoint {
+        EggGroupEndpoint::new(self.clone())
+    }
+
+    /// Access to moves API endpoint
+    pub fn moves(&self) -> MoveEndpoint {
+        MoveEndpoint::new(self.clone())
+    }
 }
 
 impl ApiClient {
+    /// Base url of the API version, e.g. `https://pokeapi.co/api/v2`
+    pub(crate) fn api_url(&self) -> &str {
+        &self.url
+    }
+
+    /// Gets the search index built before under the key.
+    pub(crate) fn search_index(&self, key: &str) -> Option<Arc<SearchIndex>> {
+        self.indexes.lock().unwrap().get(key).cloned()
+    }
+
+    /// Keeps the search index for the following searches.
+    pub(crate) fn store_search_index(&self, key: &str, index: SearchIndex) -> Arc<SearchIndex> {
+        let index = Arc::new(index);
+        self.indexes
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), index.clone());
+        index
+    }
+
+    /// Sends GET request to the given url, waiting for the rate limiter if any
+    /// and retrying transient failures according to the retry policy if any.
+    /// Error statuses are errors.
+    async fn send(&self, url: &str) -> Result<Response> {
+        Ok(self.respond(url).await?.error_for_status()?)
+    }
+
+    /// Like `send`, but gives the response whatever its status.
+    async fn respond(&self, url: &str) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            if let Some(ref limiter) = self.limiter {
+                limiter.acquire().await;
+            }
+
+            let result = self.client.get(url).send().await;
+            let delay = match (self.retry, &result) {
+                (Some(policy), _) if attempt >= policy.max_retries => None,
+                (Some(policy), Ok(response)) if RetryPolicy::retries_status(response.status()) => {
+                    Some(policy.delay(attempt, retry_after(response)))
+                }
+                (Some(policy), Err(err)) if err.is_connect() || err.is_timeout() => {
+                    Some(policy.delay(attempt, None))
+                }
+                _ => None,
+            };
+
+            match delay {
+                Some(delay) => {
+                    tokio::time::delay_for(delay).await;
+                    attempt += 1;
+                }
+                None => return Ok(result?),
+            }
+        }
+    }
+
+    /// Downloads the body of the url, bypassing the cache of responses.
+    /// The rate limiter and the retry policy apply.
+    pub(crate) async fn download(&self, url: &str) -> Result<Vec<u8>> {
+        Ok(self.send(url).await?.bytes().await?.to_vec())
+    }
+
+    /// Gets the body of the response for the url.
+    /// The cassette in the replay mode answers instead of the network,
+    /// otherwise the response comes from the cache if possible.
+    /// The cassette in the record mode keeps error responses too, with their status.
+    async fn fetch(&self, url: String) -> Result<String> {
+        if let Some(ref cassette) = self.cassette {
+            if cassette.mode() == CassetteMode::Replay {
+                return cassette.play(&url);
+            }
+        }
+
+        let body = match self.cache.as_ref().and_then(|cache| cache.get(&url)) {
+            Some(body) => body,
+            None => {
+                let response = self.respond(&url).await?;
+                if let Err(err) = response.error_for_status_ref() {
+                    if let Some(ref cassette) = self.cassette {
+                        let status = response.status().as_u16();
+                        cassette.save(&url, status, &response.text().await?)?;
+                    }
+                    return Err(err.into());
+                }
+
+                let body = response.text().await?;
+                if let Some(ref cache) = self.cache {
+                    cache.put(&url, &body);
+                }
+                body
+            }
+        };
+
+        if let Some(ref cassette) = self.cassette {
+            cassette.save(&url, 200, &body)?;
+        }
+
+        Ok(body)
+    }
+
     /// Request the API resource given the path.
     /// For example, with path `path`, will request the `https://pokeapi.co/api/v2/path`
-    pub(crate) async fn request_api<T: Into<String>>(&self, req: T) -> Result<Response> {
-        self.client
-            .get(format!("{}/{}", self.url, req.into()).as_str())
-            .send()
-            .await
-            .map_err(|err| crate::Error::from(err))
+    pub(crate) async fn request_api<T: Into<String>>(&self, req: T) -> Result<String> {
+        self.fetch(format!("{}/{}", self.url, req.into())).await
     }
 
     /// Request the API resource given the path and casts it to the type `P`.
@@ -54,17 +275,23 @@ impl ApiClient {
     where
         P: Sized + serde::de::DeserializeOwned,
     {
-        self.client
-            .get(format!("{}/{}", self.url, req.into()).as_str())
-            .send()
-            .await
-            .map_err(|err| crate::Error::from(err))?
-            .json::<P>()
+        self.decode(&self.request_api(req).await?)
+    }
+
+    /// Request the API resource given the path and casts it to the type `P`,
+    /// keeping the JSON document of the response.
+    pub(crate) async fn request_api_with_raw<P, T: Into<String>>(
+        &self,
+        req: T,
+    ) -> Result<WithRaw<P>>
+    where
+        P: Sized + serde::de::DeserializeOwned,
+    {
+        self.request_with_raw(format!("{}/{}", self.url, req.into()))
             .await
-            .map_err(|err| crate::Error::from(err))
     }
 
-    /// Request the API resource give nthe path and pagination parameters.
+    /// Request the API resource given the path and pagination parameters.
     /// Casts the result to the model type `P` which has to be deserializable.
     pub(crate) async fn request_api_object_paginated<P, T: Into<String>>(
         &self,
@@ -75,31 +302,62 @@ impl ApiClient {
     where
         P: Sized + serde::de::DeserializeOwned,
     {
-        self.client
-            .get(
-                format!(
-                    "{}/{}?offset={}&limit={}",
-                    self.url,
-                    req.into(),
-                    offset,
-                    limit
-                )
-                .as_str(),
-            )
-            .send()
-            .await
-            .map_err(|err| crate::Error::from(err))?
-            .json::<P>()
-            .await
-            .map_err(|err| crate::Error::from(err))
+        self.request_object(format!(
+            "{}/{}?offset={}&limit={}",
+            self.url,
+            req.into(),
+            offset,
+            limit
+        ))
+        .await
     }
 
     /// Request given url.
-    pub(crate) async fn request<T: Into<String>>(&self, req: T) -> Result<Response> {
-        self.client
-            .get(req.into().as_str())
-            .send()
-            .await
-            .map_err(|err| crate::Error::from(err))
+    pub(crate) async fn request<T: Into<String>>(&self, req: T) -> Result<String> {
+        self.fetch(req.into()).await
     }
+
+    /// Request given url and casts the response to the type `P`.
+    pub(crate) async fn request_object<P, T: Into<String>>(&self, req: T) -> Result<P>
+    where
+        P: Sized + serde::de::DeserializeOwned,
+    {
+        self.decode(&self.request(req).await?)
+    }
+
+    /// Request given url and casts the response to the type `P`,
+    /// keeping the JSON document of the response.
+    pub(crate) async fn request_with_raw<P, T: Into<String>>(&self, req: T) -> Result<WithRaw<P>>
+    where
+        P: Sized + serde::de::DeserializeOwned,
+    {
+        let body = self.request(req).await?;
+
+        Ok(WithRaw {
+            raw: serde_json::from_str(&body)?,
+            model: self.decode(&body)?,
+        })
+    }
+
+    /// Casts the response body to the type `P` in the decode mode of the client.
+    /// The drift report is locked only to merge the drift of this response,
+    /// so concurrent requests deserialize in parallel.
+    fn decode<P: serde::de::DeserializeOwned>(&self, body: &str) -> Result<P> {
+        if self.decode_mode == DecodeMode::Normal {
+            return Ok(serde_json::from_str(body)?);
+        }
+
+        let mut drift = DriftReport::default();
+        let model = schema::decode(body, self.decode_mode, &mut drift)?;
+        if !drift.is_empty() {
+            self.drift.lock().unwrap().merge(drift);
+        }
+        Ok(model)
+    }
+}
+
+/// Delay requested by the `Retry-After` header given in seconds.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?;
+    value.to_str().ok()?.parse().ok().map(Duration::from_secs)
 }
@@ -1,9 +1,19 @@
 use super::client::ApiClient;
+use super::expand::{Expanded, Expander};
+use super::key::ResourceKey;
+use super::raw::WithRaw;
+use super::search::{SearchHit, SearchIndex};
 use crate::api::v2::resource::ApiNamedResourceList;
+use crate::models::v2::common::HasNames;
 use crate::models::v2::resource::NamedResourceList;
 use crate::Result;
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Pagination limit big enough to get all resources of any endpoint in a single request.
+pub const MAX_PAGINATION_LIMIT: usize = 100_000;
 
 /// Trait for accessing API endpoint.
 /// Encapsulates common methods.
@@ -69,4 +79,149 @@ pub trait ApiEndpoint {
             ))
             .await
     }
+
+    /// Gets a resource by its key, either id or name.
+    /// For example, given the key parsed from `"Very Soft"` will result in the following request
+    /// `https://pokeapi.co/api/v2/{endpoint-name}/very-soft`
+    async fn get<K: Into<ResourceKey> + Send>(&self, key: K) -> Result<Self::Model> {
+        self.client()
+            .request_api_object::<Self::Model, _>(format!("{}/{}", Self::name(), key.into()))
+            .await
+    }
+
+    /// Gets the JSON document of the resource by its id, including fields not modeled yet.
+    async fn get_raw_by_id(&self, id: usize) -> Result<Value> {
+        self.client()
+            .request_api_object::<Value, _>(format!("{}/{}", Self::name(), id))
+            .await
+    }
+
+    /// Gets the JSON document of the resource by its name, including fields not modeled yet.
+    async fn get_raw_by_name<T: Into<String> + Send>(&self, name: T) -> Result<Value> {
+        self.client()
+            .request_api_object::<Value, _>(format!("{}/{}", Self::name(), name.into().as_str()))
+            .await
+    }
+
+    /// Gets an object by its id together with its JSON document.
+    async fn get_with_raw_by_id(&self, id: usize) -> Result<WithRaw<Self::Model>> {
+        self.client()
+            .request_api_with_raw(format!("{}/{}", Self::name(), id))
+            .await
+    }
+
+    /// Gets a resource by its name together with its JSON document.
+    async fn get_with_raw_by_name<T: Into<String> + Send>(
+        &self,
+        name: T,
+    ) -> Result<WithRaw<Self::Model>> {
+        self.client()
+            .request_api_with_raw(format!("{}/{}", Self::name(), name.into().as_str()))
+            .await
+    }
+
+    /// Gets an object by its id with the references under the paths resolved,
+    /// e.g. `&["firmness", "flavors.flavor"]` for a berry. See `Expander` for the limits.
+    async fn get_expanded_by_id(&self, id: usize, paths: &[&str]) -> Result<Expanded<Self::Model>>
+    where
+        Self::Model: Send,
+    {
+        let url = format!("{}/{}/{}", self.client().api_url(), Self::name(), id);
+        Expander::new(self.client()).expand_url(&url, paths).await
+    }
+
+    /// Gets a resource by its name with the references under the paths resolved.
+    async fn get_expanded_by_name<T: Into<String> + Send>(
+        &self,
+        name: T,
+        paths: &[&str],
+    ) -> Result<Expanded<Self::Model>>
+    where
+        Self::Model: Send,
+    {
+        let url = format!(
+            "{}/{}/{}",
+            self.client().api_url(),
+            Self::name(),
+            name.into().as_str()
+        );
+        Expander::new(self.client()).expand_url(&url, paths).await
+    }
+
+    /// Gets the search index of all resources of the endpoint.
+    /// The resource list is fetched once with the maximum limit and cached in the client.
+    async fn search_index(&self) -> Result<Arc<SearchIndex>> {
+        if let Some(index) = self.client().search_index(Self::name()) {
+            return Ok(index);
+        }
+
+        let list = self
+            .client()
+            .request_api_object_paginated::<NamedResourceList, _>(
+                Self::name(),
+                0,
+                MAX_PAGINATION_LIMIT,
+            )
+            .await?;
+
+        Ok(self
+            .client()
+            .store_search_index(Self::name(), SearchIndex::new(list)))
+    }
+
+    /// Finds resources whose names match the query, best matches first.
+    /// Matches by prefix, substring and with typos, e.g. `chry` finds `cheri`.
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>> {
+        Ok(self.search_index().await?.search(query))
+    }
+
+    /// Gets the search index including localized names of the resources.
+    /// Building it requests every resource of the endpoint, the index is cached in the client.
+    async fn localized_search_index(&self) -> Result<Arc<SearchIndex>>
+    where
+        Self::Model: HasNames + Send,
+    {
+        let key = format!("{}#names", Self::name());
+        if let Some(index) = self.client().search_index(&key) {
+            return Ok(index);
+        }
+
+        let mut index = (*self.search_index().await?).clone();
+        let resources: Vec<_> = index.resources().cloned().collect();
+
+        for resource in resources {
+            let model = self
+                .client()
+                .request_object::<Self::Model, _>(resource.url.as_str())
+                .await?;
+            index.add_names(&resource.url, model.names());
+        }
+
+        Ok(self.client().store_search_index(&key, index))
+    }
+
+    /// Finds resources whose names or localized names match the query, best matches first.
+    async fn search_localized(&self, query: &str) -> Result<Vec<SearchHit>>
+    where
+        Self::Model: HasNames + Send,
+    {
+        Ok(self.localized_search_index().await?.search(query))
+    }
+}
+
+/// Fetches every resource of the endpoint.
+/// Responses go through the client cache, if it has one.
+pub(crate) async fn fetch_all<E>(endpoint: &E) -> Result<Vec<E::Model>>
+where
+    E: ApiEndpoint + Sync,
+    E::NamedResourceList: ApiNamedResourceList<ResourceType = E::Model>,
+{
+    let list = endpoint.all_paginated(0, MAX_PAGINATION_LIMIT).await?;
+
+    let mut models = Vec::with_capacity(list.count());
+    for resource in list.resources() {
+        models.push(resource.get().await?);
+    }
+
+    Ok(models)
 }
@@ -0,0 +1,378 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Deref;
+
+use futures::future::try_join_all;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::api::v2::client::ApiClient;
+use crate::api::v2::raw::WithRaw;
+use crate::models::v2::resource::normalize_url;
+use crate::{Error, Result};
+
+/// Number of references followed from the root resource by default.
+pub const DEFAULT_MAX_DEPTH: usize = 3;
+
+/// Model together with its JSON document where the requested references
+/// are replaced by the documents of the referenced resources.
+#[derive(Clone, Debug)]
+pub struct Expanded<T> {
+    /// Decoded model, references are left as they are.
+    pub model: T,
+
+    /// Expanded JSON document of the model.
+    pub value: Value,
+}
+
+impl<T> Expanded<T> {
+    /// Decodes the values found under the dot separated path, looking through lists,
+    /// e.g. `flavors.flavor` gives the expanded flavors of a berry.
+    pub fn resolved<M: DeserializeOwned>(&self, path: &str) -> Result<Vec<M>> {
+        let mut values = vec![&self.value];
+        for field in path.split('.').filter(|f| !f.is_empty()) {
+            values = values
+                .into_iter()
+                .flat_map(|value| match value {
+                    Value::Array(items) => items.iter().collect(),
+                    value => vec![value],
+                })
+                .filter_map(|value| value.get(field))
+                .collect();
+        }
+
+        values
+            .into_iter()
+            .flat_map(|value| match value {
+                Value::Array(items) => items.iter().collect(),
+                value => vec![value],
+            })
+            .map(|value| Ok(serde_json::from_value(value.clone())?))
+            .collect()
+    }
+}
+
+impl<T> Deref for Expanded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.model
+    }
+}
+
+/// Tree of the requested paths, e.g. `firmness` and `firmness.berries` share the root field.
+#[derive(Debug, Default)]
+struct PathTree(BTreeMap<String, PathTree>);
+
+impl PathTree {
+    fn new(paths: &[&str]) -> Self {
+        let mut tree = PathTree::default();
+        for path in paths {
+            let mut node = &mut tree;
+            for field in path.split('.').filter(|f| !f.is_empty()) {
+                node = node.0.entry(field.to_string()).or_default();
+            }
+        }
+        tree
+    }
+}
+
+/// Reference waiting to be resolved.
+struct Pending<'t> {
+    /// JSON pointer of the reference inside the expanded document.
+    pointer: String,
+    url: String,
+    tree: &'t PathTree,
+    /// Urls of the resources expanded on the way from the root, the root included.
+    ancestors: Vec<String>,
+}
+
+/// Resolves references of a JSON document.
+///
+/// References along the requested paths are fetched level by level, all references of
+/// a level concurrently and each distinct url once. Expansion stops after `max_depth`
+/// references from the root, and references to a resource already expanded on the way
+/// from the root are left as they are, so cycles like `firmness.berries.firmness` end.
+pub struct Expander<'a> {
+    client: &'a ApiClient,
+    max_depth: usize,
+}
+
+impl<'a> Expander<'a> {
+    /// Creates expander fetching resources with the client.
+    pub fn new(client: &'a ApiClient) -> Self {
+        Self {
+            client,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Follows at most `depth` references from the root.
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Gets the resource at the url and expands its document along the paths.
+    pub async fn expand_url<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        paths: &[&str],
+    ) -> Result<Expanded<T>> {
+        let WithRaw { model, raw } = self.client.request_with_raw(url).await?;
+
+        Ok(Expanded {
+            model,
+            value: self
+                .expand_from(raw, paths, vec![normalize_url(url)])
+                .await?,
+        })
+    }
+
+    /// Expands the document along the dot separated paths, e.g. `flavors.flavor`.
+    /// Lists are looked through, references on the way are expanded too.
+    pub async fn expand(&self, value: Value, paths: &[&str]) -> Result<Value> {
+        self.expand_from(value, paths, Vec::new()).await
+    }
+
+    async fn expand_from(
+        &self,
+        mut value: Value,
+        paths: &[&str],
+        root: Vec<String>,
+    ) -> Result<Value> {
+        let tree = PathTree::new(paths);
+        let mut pending = Vec::new();
+        collect(&value, String::new(), &tree, &root, &mut pending)?;
+
+        let mut fetched: HashMap<String, Value> = HashMap::new();
+        let mut depth = 1;
+        while depth <= self.max_depth && !pending.is_empty() {
+            pending.retain(|p| !p.ancestors.contains(&p.url));
+
+            let mut urls: Vec<String> = pending
+                .iter()
+                .map(|p| p.url.clone())
+                .filter(|url| !fetched.contains_key(url))
+                .collect();
+            urls.sort_unstable();
+            urls.dedup();
+            let documents = try_join_all(
+                urls.iter()
+                    .map(|url| self.client.request_object::<Value, _>(url.as_str())),
+            )
+            .await?;
+            fetched.extend(urls.into_iter().zip(documents));
+
+            let mut next = Vec::new();
+            for reference in pending {
+                if let Some(slot) = value.pointer_mut(&reference.pointer) {
+                    *slot = fetched[&reference.url].clone();
+                }
+
+                let mut ancestors = reference.ancestors;
+                ancestors.push(reference.url);
+                if let Some(document) = value.pointer(&reference.pointer) {
+                    collect(
+                        document,
+                        reference.pointer,
+                        reference.tree,
+                        &ancestors,
+                        &mut next,
+                    )?;
+                }
+            }
+            pending = next;
+            depth += 1;
+        }
+
+        Ok(value)
+    }
+}
+
+/// Finds the references under the paths of the tree, without following references.
+fn collect<'t>(
+    value: &Value,
+    pointer: String,
+    tree: &'t PathTree,
+    ancestors: &[String],
+    pending: &mut Vec<Pending<'t>>,
+) -> Result<()> {
+    match value {
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect(
+                    item,
+                    format!("{}/{}", pointer, index),
+                    tree,
+                    ancestors,
+                    pending,
+                )?;
+            }
+        }
+        Value::Object(map) => {
+            for (field, subtree) in tree.0.iter() {
+                let child = map.get(field).ok_or_else(|| {
+                    Error::InvalidArgument(format!("no field `{}` to expand", field))
+                })?;
+                let pointer = format!("{}/{}", pointer, escape(field));
+                collect_field(child, pointer, subtree, ancestors, pending)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Queues the field value if it is a reference, otherwise looks for references inside it.
+fn collect_field<'t>(
+    value: &Value,
+    pointer: String,
+    tree: &'t PathTree,
+    ancestors: &[String],
+    pending: &mut Vec<Pending<'t>>,
+) -> Result<()> {
+    match value {
+        Value::Object(map) if is_reference(map) => {
+            pending.push(Pending {
+                pointer,
+                url: normalize_url(map["url"].as_str().unwrap_or_default()),
+                tree,
+                ancestors: ancestors.to_vec(),
+            });
+            Ok(())
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                let pointer = format!("{}/{}", pointer, index);
+                collect_field(item, pointer, tree, ancestors, pending)?;
+            }
+            Ok(())
+        }
+        value => collect(value, pointer, tree, ancestors, pending),
+    }
+}
+
+/// Whether the object is `NamedResource` or `Resource`.
+pub(crate) fn is_reference(map: &Map<String, Value>) -> bool {
+    map.get("url").is_some_and(Value::is_string)
+        && map.keys().all(|key| key == "name" || key == "url")
+}
+
+/// Escapes the JSON pointer segment.
+fn escape(field: &str) -> String {
+    field.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod test {
+    use super::Expander;
+    use crate::api::v2::endpoint::ApiEndpoint;
+    use crate::models::v2::berry::{BerryFirmness, BerryFlavor};
+    use crate::testing::MockServer;
+    use serde_json::json;
+
+    fn server() -> MockServer {
+        let server = MockServer::start().unwrap();
+        server.add_resource(
+            "berry",
+            json!({
+                "id": 1,
+                "name": "cheri",
+                "growth_time": 3,
+                "max_harvest": 5,
+                "natural_gift_power": 60,
+                "size": 20,
+                "smoothness": 25,
+                "soil_dryness": 15,
+                "item": { "name": "cheri-berry", "url": "https://pokeapi.co/api/v2/item/126/" },
+                "natural_gift_type": { "name": "fire", "url": "https://pokeapi.co/api/v2/type/10/" },
+                "firmness": { "name": "soft", "url": "https://pokeapi.co/api/v2/berry-firmness/2/" },
+                "flavors": [
+                    { "potency": 10, "flavor": { "name": "spicy", "url": "https://pokeapi.co/api/v2/berry-flavor/1/" } },
+                    { "potency": 0, "flavor": { "name": "dry", "url": "https://pokeapi.co/api/v2/berry-flavor/2/" } }
+                ]
+            }),
+        );
+        server.add_resource(
+            "berry-firmness",
+            json!({
+                "id": 2,
+                "name": "soft",
+                "berries": [{ "name": "cheri", "url": "https://pokeapi.co/api/v2/berry/1/" }],
+                "names": []
+            }),
+        );
+        for (id, name) in [(1, "spicy"), (2, "dry")].iter() {
+            server.add_resource(
+                "berry-flavor",
+                json!({
+                    "id": id,
+                    "name": name,
+                    "berries": [],
+                    "contest_type": { "name": "cool", "url": "https://pokeapi.co/api/v2/contest-type/1/" },
+                    "names": []
+                }),
+            );
+        }
+        server
+    }
+
+    #[tokio::test]
+    async fn expands_paths() {
+        let server = server();
+        let client = server.client().unwrap();
+
+        let cheri = client
+            .berries()
+            .get_expanded_by_id(1, &["firmness", "flavors.flavor"])
+            .await
+            .unwrap();
+        assert_eq!(cheri.name, "cheri");
+        assert_eq!(cheri.value["firmness"]["id"], 2);
+
+        let flavors: Vec<BerryFlavor> = cheri.resolved("flavors.flavor").unwrap();
+        let names: Vec<&str> = flavors.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["spicy", "dry"]);
+        let firmness: Vec<BerryFirmness> = cheri.resolved("firmness").unwrap();
+        assert_eq!(firmness[0].berries[0].name, "cheri");
+
+        // The berry, its firmness and two flavors.
+        assert_eq!(server.requests().len(), 4);
+
+        assert!(client
+            .berries()
+            .get_expanded_by_id(1, &["color"])
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn stops_at_cycles_and_depth() {
+        let server = server();
+        let client = server.client().unwrap();
+        let url = format!("{}/v2/berry/1/", server.base_url());
+
+        // The berry of the firmness is the root berry itself.
+        let cheri = Expander::new(&client)
+            .expand_url::<serde_json::Value>(&url, &["firmness.berries.firmness"])
+            .await
+            .unwrap();
+        assert_eq!(cheri.value["firmness"]["berries"][0]["name"], "cheri");
+        assert!(cheri.value["firmness"]["berries"][0].get("id").is_none());
+
+        let value = client.berries().get_raw_by_id(1).await.unwrap();
+        let shallow = Expander::new(&client)
+            .with_max_depth(1)
+            .expand(value, &["flavors.flavor.contest_type"])
+            .await
+            .unwrap();
+        assert_eq!(shallow["flavors"][0]["flavor"]["name"], "spicy");
+        assert_eq!(
+            shallow["flavors"][0]["flavor"]["contest_type"]["name"],
+            "cool"
+        );
+        assert!(shallow["flavors"][0]["flavor"]["contest_type"]
+            .get("id")
+            .is_none());
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+use std::str::FromStr;
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::Error;
+
+/// Key identifying a resource of an endpoint: either its id or its name.
+///
+/// Parsing a key from a string treats digits as an id and normalizes anything else
+/// to the PokeAPI slug: lowercase, words separated by hyphens, accents and punctuation stripped.
+/// For example `"Farfetch’d"` becomes `farfetchd` and `"Mr. Mime"` becomes `mr-mime`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ResourceKey {
+    /// The identifier of the resource.
+    Id(usize),
+
+    /// The name of the resource, as a PokeAPI slug.
+    Name(String),
+}
+
+impl ResourceKey {
+    /// Converts an arbitrary name into the PokeAPI slug.
+    pub fn slug(name: &str) -> String {
+        let mut slug = String::with_capacity(name.len());
+
+        for c in name.nfkd().filter(|c| !is_combining_mark(*c)) {
+            match c {
+                '♀' => slug.push_str("-f"),
+                '♂' => slug.push_str("-m"),
+                c if c.is_alphanumeric() => slug.extend(c.to_lowercase()),
+                c if is_separator(c) && !slug.is_empty() && !slug.ends_with('-') => slug.push('-'),
+                _ => {}
+            }
+        }
+
+        slug.trim_matches('-').to_string()
+    }
+}
+
+/// Characters separating words of a name.
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || c == '-' || c == '_'
+}
+
+impl FromStr for ResourceKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()) {
+            return s
+                .parse()
+                .map(ResourceKey::Id)
+                .map_err(|_| Error::InvalidArgument(format!("resource id `{}` is too big", s)));
+        }
+
+        match ResourceKey::slug(s) {
+            ref slug if slug.is_empty() => Err(Error::InvalidArgument(format!(
+                "`{}` is not a valid resource name",
+                s
+            ))),
+            slug => Ok(ResourceKey::Name(slug)),
+        }
+    }
+}
+
+impl From<usize> for ResourceKey {
+    fn from(id: usize) -> Self {
+        ResourceKey::Id(id)
+    }
+}
+
+impl fmt::Display for ResourceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceKey::Id(id) => write!(f, "{}", id),
+            ResourceKey::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ResourceKey;
+
+    #[test]
+    fn parses_ids() {
+        assert_eq!("1".parse::<ResourceKey>().unwrap(), ResourceKey::Id(1));
+        assert_eq!(" 25 ".parse::<ResourceKey>().unwrap(), ResourceKey::Id(25));
+        assert_eq!(ResourceKey::from(5), ResourceKey::Id(5));
+    }
+
+    #[test]
+    fn normalizes_names() {
+        let cases = [
+            ("cheri", "cheri"),
+            ("Cheri Berry", "cheri-berry"),
+            ("Farfetch’d", "farfetchd"),
+            ("Mr. Mime", "mr-mime"),
+            ("Type: Null", "type-null"),
+            ("Flabébé", "flabebe"),
+            ("Nidoran♀", "nidoran-f"),
+            ("  very_soft  ", "very-soft"),
+            ("Porygon-Z", "porygon-z"),
+            ("super -- hard", "super-hard"),
+        ];
+
+        for (name, slug) in cases.iter() {
+            assert_eq!(
+                name.parse::<ResourceKey>().unwrap(),
+                ResourceKey::Name(slug.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_empty_names() {
+        assert!("".parse::<ResourceKey>().is_err());
+        assert!(" ’.! ".parse::<ResourceKey>().is_err());
+    }
+
+    #[test]
+    fn displays_path_segment() {
+        assert_eq!(ResourceKey::Id(3).to_string(), "3");
+        assert_eq!(
+            "Very Soft".parse::<ResourceKey>().unwrap().to_string(),
+            "very-soft"
+        );
+    }
+}
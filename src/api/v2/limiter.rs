@@ -0,0 +1,56 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Simple rate limiter spacing requests by a minimal interval.
+/// Clones share the same schedule, so every clone of an `ApiClient` is throttled together.
+#[derive(Clone, Debug)]
+pub(crate) struct RateLimiter {
+    /// Minimal interval between two consecutive requests
+    interval: Duration,
+
+    /// Moment when the next request is allowed to be sent
+    next: Arc<Mutex<Instant>>,
+}
+
+impl RateLimiter {
+    /// Creates rate limiter allowing one request per `interval`.
+    pub(crate) fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Waits until the next request slot is available and reserves it.
+    pub(crate) async fn acquire(&self) {
+        let wait = {
+            let mut next = self.next.lock().unwrap();
+            let now = Instant::now();
+            let slot = if *next > now { *next } else { now };
+            *next = slot + self.interval;
+            slot - now
+        };
+
+        if wait > Duration::from_millis(0) {
+            tokio::time::delay_for(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RateLimiter;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn spaces_requests() {
+        let limiter = RateLimiter::new(Duration::from_millis(50));
+        let started = Instant::now();
+
+        limiter.acquire().await;
+        limiter.clone().acquire().await;
+        limiter.acquire().await;
+
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -2,4 +2,15 @@ pub mod client;
 #[macro_use]
 pub mod resource;
 pub mod berry;
+pub mod cache;
+pub mod cassette;
 pub mod endpoint;
+pub mod expand;
+pub mod key;
+mod limiter;
+pub mod moves;
+pub mod pokemon;
+pub mod raw;
+pub mod retry;
+pub mod schema;
+pub mod search;
@@ -0,0 +1,42 @@
+use super::client::ApiClient;
+use super::endpoint::ApiEndpoint;
+use super::resource::*;
+use crate::models::v2::moves::Move;
+use crate::models::v2::resource::NamedResourceList;
+use crate::Result;
+
+/// Moves API endpoint. For details see [pokeapi/moves](https://pokeapi.co/docs/v2.html#moves)
+/// Represents the endpoint `https://pokeapi.co/api/v2/move`
+#[derive(Clone)]
+pub struct MoveEndpoint {
+    client: ApiClient,
+}
+
+impl ApiEndpoint for MoveEndpoint {
+    type Model = Move;
+    type NamedResourceList = MoveNamedResourceList;
+
+    fn client(&self) -> &ApiClient {
+        &self.client
+    }
+
+    fn name() -> &'static str {
+        "move"
+    }
+
+    fn create_named_resource_list(&self, model: NamedResourceList) -> Self::NamedResourceList {
+        MoveNamedResourceList::new(self.client.clone(), model)
+    }
+}
+
+impl MoveEndpoint {
+    /// Creates API Endpoint object
+    pub(crate) fn new(client: ApiClient) -> Self {
+        Self { client }
+    }
+}
+
+decl_resource!(MoveResource for Move);
+decl_named_resource!(MoveNamedResource for Move);
+decl_resource_list!(MoveResourceList for Move with MoveResource);
+decl_named_resource_list!(MoveNamedResourceList for Move with MoveNamedResource);
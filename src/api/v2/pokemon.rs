@@ -0,0 +1,186 @@
+use super::client::ApiClient;
+use super::endpoint::ApiEndpoint;
+use super::resource::*;
+use crate::models::v2::pokemon::{EggGroup, Nature, Pokemon, PokemonSpecies, Type};
+use crate::models::v2::resource::NamedResourceList;
+use crate::Result;
+
+/// Types API endpoint. For details see [pokeapi/types](https://pokeapi.co/docs/v2.html#types)
+/// Represents the endpoint `https://pokeapi.co/api/v2/type`
+#[derive(Clone)]
+pub struct TypeEndpoint {
+    client: ApiClient,
+}
+
+impl ApiEndpoint for TypeEndpoint {
+    type Model = Type;
+    type NamedResourceList = TypeNamedResourceList;
+
+    fn client(&self) -> &ApiClient {
+        &self.client
+    }
+
+    fn name() -> &'static str {
+        "type"
+    }
+
+    fn create_named_resource_list(&self, model: NamedResourceList) -> Self::NamedResourceList {
+        TypeNamedResourceList::new(self.client.clone(), model)
+    }
+}
+
+impl TypeEndpoint {
+    /// Creates API Endpoint object
+    pub(crate) fn new(client: ApiClient) -> Self {
+        Self { client }
+    }
+}
+
+decl_resource!(TypeResource for Type);
+decl_named_resource!(TypeNamedResource for Type);
+decl_resource_list!(TypeResourceList for Type with TypeResource);
+decl_named_resource_list!(TypeNamedResourceList for Type with TypeNamedResource);
+
+/// Pokémon API endpoint. For details see [pokeapi/pokemon](https://pokeapi.co/docs/v2.html#pokemon)
+/// Represents the endpoint `https://pokeapi.co/api/v2/pokemon`
+#[derive(Clone)]
+pub struct PokemonEndpoint {
+    client: ApiClient,
+}
+
+impl ApiEndpoint for PokemonEndpoint {
+    type Model = Pokemon;
+    type NamedResourceList = PokemonNamedResourceList;
+
+    fn client(&self) -> &ApiClient {
+        &self.client
+    }
+
+    fn name() -> &'static str {
+        "pokemon"
+    }
+
+    fn create_named_resource_list(&self, model: NamedResourceList) -> Self::NamedResourceList {
+        PokemonNamedResourceList::new(self.client.clone(), model)
+    }
+}
+
+impl PokemonEndpoint {
+    /// Creates API Endpoint object
+    pub(crate) fn new(client: ApiClient) -> Self {
+        Self { client }
+    }
+}
+
+decl_resource!(PokemonResource for Pokemon);
+decl_named_resource!(PokemonNamedResource for Pokemon);
+decl_resource_list!(PokemonResourceList for Pokemon with PokemonResource);
+decl_named_resource_list!(PokemonNamedResourceList for Pokemon with PokemonNamedResource);
+
+/// Natures API endpoint. For details see [pokeapi/natures](https://pokeapi.co/docs/v2.html#natures)
+/// Represents the endpoint `https://pokeapi.co/api/v2/nature`
+#[derive(Clone)]
+pub struct NatureEndpoint {
+    client: ApiClient,
+}
+
+impl ApiEndpoint for NatureEndpoint {
+    type Model = Nature;
+    type NamedResourceList = NatureNamedResourceList;
+
+    fn client(&self) -> &ApiClient {
+        &self.client
+    }
+
+    fn name() -> &'static str {
+        "nature"
+    }
+
+    fn create_named_resource_list(&self, model: NamedResourceList) -> Self::NamedResourceList {
+        NatureNamedResourceList::new(self.client.clone(), model)
+    }
+}
+
+impl NatureEndpoint {
+    /// Creates API Endpoint object
+    pub(crate) fn new(client: ApiClient) -> Self {
+        Self { client }
+    }
+}
+
+decl_resource!(NatureResource for Nature);
+decl_named_resource!(NatureNamedResource for Nature);
+decl_resource_list!(NatureResourceList for Nature with NatureResource);
+decl_named_resource_list!(NatureNamedResourceList for Nature with NatureNamedResource);
+
+/// Pokémon species API endpoint. For details see [pokeapi/pokemon-species](https://pokeapi.co/docs/v2.html#pokemon-species)
+/// Represents the endpoint `https://pokeapi.co/api/v2/pokemon-species`
+#[derive(Clone)]
+pub struct PokemonSpeciesEndpoint {
+    client: ApiClient,
+}
+
+impl ApiEndpoint for PokemonSpeciesEndpoint {
+    type Model = PokemonSpecies;
+    type NamedResourceList = PokemonSpeciesNamedResourceList;
+
+    fn client(&self) -> &ApiClient {
+        &self.client
+    }
+
+    fn name() -> &'static str {
+        "pokemon-species"
+    }
+
+    fn create_named_resource_list(&self, model: NamedResourceList) -> Self::NamedResourceList {
+        PokemonSpeciesNamedResourceList::new(self.client.clone(), model)
+    }
+}
+
+impl PokemonSpeciesEndpoint {
+    /// Creates API Endpoint object
+    pub(crate) fn new(client: ApiClient) -> Self {
+        Self { client }
+    }
+}
+
+decl_resource!(PokemonSpeciesResource for PokemonSpecies);
+decl_named_resource!(PokemonSpeciesNamedResource for PokemonSpecies);
+decl_resource_list!(PokemonSpeciesResourceList for PokemonSpecies with PokemonSpeciesResource);
+decl_named_resource_list!(PokemonSpeciesNamedResourceList for PokemonSpecies with PokemonSpeciesNamedResource);
+
+/// Egg groups API endpoint. For details see [pokeapi/egg-groups](https://pokeapi.co/docs/v2.html#egg-groups)
+/// Represents the endpoint `https://pokeapi.co/api/v2/egg-group`
+#[derive(Clone)]
+pub struct EggGroupEndpoint {
+    client: ApiClient,
+}
+
+impl ApiEndpoint for EggGroupEndpoint {
+    type Model = EggGroup;
+    type NamedResourceList = EggGroupNamedResourceList;
+
+    fn client(&self) -> &ApiClient {
+        &self.client
+    }
+
+    fn name() -> &'static str {
+        "egg-group"
+    }
+
+    fn create_named_resource_list(&self, model: NamedResourceList) -> Self::NamedResourceList {
+        EggGroupNamedResourceList::new(self.client.clone(), model)
+    }
+}
+
+impl EggGroupEndpoint {
+    /// Creates API Endpoint object
+    pub(crate) fn new(client: ApiClient) -> Self {
+        Self { client }
+    }
+}
+
+decl_resource!(EggGroupResource for EggGroup);
+decl_named_resource!(EggGroupNamedResource for EggGroup);
+decl_resource_list!(EggGroupResourceList for EggGroup with EggGroupResource);
+decl_named_resource_list!(EggGroupNamedResourceList for EggGroup with EggGroupNamedResource);
@@ -0,0 +1,84 @@
+//! Models paired with the JSON documents they were decoded from.
+
+use std::ops::Deref;
+
+use serde_json::Value;
+
+/// Typed model together with the JSON document it was decoded from.
+/// The raw document keeps the fields the model does not cover yet.
+#[derive(Clone, Debug)]
+pub struct WithRaw<T> {
+    /// Decoded model.
+    pub model: T,
+
+    /// Original JSON document of the response.
+    pub raw: Value,
+}
+
+impl<T> WithRaw<T> {
+    /// Value of the raw document at the JSON pointer, like `/flavors/0/flavor/name`.
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        self.raw.pointer(pointer)
+    }
+
+    /// Drops the raw document.
+    pub fn into_model(self) -> T {
+        self.model
+    }
+}
+
+impl<T> Deref for WithRaw<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.model
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::api::v2::endpoint::ApiEndpoint;
+    use crate::testing::MockServer;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn keeps_unmodeled_fields() {
+        let server = MockServer::start().unwrap();
+        server.add_resource(
+            "berry-firmness",
+            json!({
+                "id": 2,
+                "name": "soft",
+                "berries": [{ "name": "cheri", "url": "" }],
+                "names": [],
+                "hardness": 3
+            }),
+        );
+        let client = server.client().unwrap();
+
+        let raw = client.berry_firmnesses().get_raw_by_id(2).await.unwrap();
+        assert_eq!(raw["hardness"], 3);
+        let raw = client
+            .berry_firmnesses()
+            .get_raw_by_name("soft")
+            .await
+            .unwrap();
+        assert_eq!(raw["berries"][0]["name"], "cheri");
+
+        let soft = client
+            .berry_firmnesses()
+            .get_with_raw_by_name("soft")
+            .await
+            .unwrap();
+        assert_eq!(soft.name, "soft");
+        assert_eq!(soft.pointer("/hardness"), Some(&json!(3)));
+        assert_eq!(soft.into_model().id, 2);
+
+        let soft = client
+            .berry_firmnesses()
+            .get_with_raw_by_id(2)
+            .await
+            .unwrap();
+        assert_eq!(soft.raw["id"], soft.id);
+    }
+}
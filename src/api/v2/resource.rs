@@ -67,12 +67,9 @@ macro_rules! decl_resource {
             }
 
             async fn get(&self) -> Result<Self::ResourceType> {
-                Ok(self
-                    .client
-                    .request(self.url())
-                    .await?
-                    .json::<Self::ResourceType>()
-                    .await?)
+                self.client
+                    .request_object::<Self::ResourceType, _>(self.url())
+                    .await
             }
         }
     };
@@ -104,12 +101,9 @@ macro_rules! decl_named_resource {
             }
 
             async fn get(&self) -> Result<Self::ResourceType> {
-                Ok(self
-                    .client
-                    .request(self.url())
-                    .await?
-                    .json::<Self::ResourceType>()
-                    .await?)
+                self.client
+                    .request_object::<Self::ResourceType, _>(self.url())
+                    .await
             }
         }
 
@@ -155,9 +149,7 @@ macro_rules! decl_resource_list {
                         Ok(Some(Box::new(Self::new(
                             self.client.clone(),
                             self.client
-                                .request(url)
-                                .await?
-                                .json::<$crate::models::v2::resource::ResourceList>()
+                                .request_object::<$crate::models::v2::resource::ResourceList, _>(url)
                                 .await?
                         ))))
                     }
@@ -172,9 +164,7 @@ macro_rules! decl_resource_list {
                         Ok(Some(Box::new(Self::new(
                             self.client.clone(),
                             self.client
-                                .request(url)
-                                .await?
-                                .json::<$crate::models::v2::resource::ResourceList>()
+                                .request_object::<$crate::models::v2::resource::ResourceList, _>(url)
                                 .await?
                         ))))
                     }
@@ -227,9 +217,7 @@ macro_rules! decl_named_resource_list {
                         Ok(Some(Box::new(Self::new(
                             self.client.clone(),
                             self.client
-                                .request(url)
-                                .await?
-                                .json::<$crate::models::v2::resource::NamedResourceList>()
+                                .request_object::<$crate::models::v2::resource::NamedResourceList, _>(url)
                                 .await?
                         ))))
                     }
@@ -244,9 +232,7 @@ macro_rules! decl_named_resource_list {
                         Ok(Some(Box::new(Self::new(
                             self.client.clone(),
                             self.client
-                                .request(url)
-                                .await?
-                                .json::<$crate::models::v2::resource::NamedResourceList>()
+                                .request_object::<$crate::models::v2::resource::NamedResourceList, _>(url)
                                 .await?
                         ))))
                     }
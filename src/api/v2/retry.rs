@@ -0,0 +1,112 @@
+//! Retries of requests failing with transient errors, see `ApiClient::with_retry`.
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+/// Upper bound of the delay between two attempts.
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Policy of repeating requests that failed with a transient error:
+/// connection failures, `429 Too Many Requests` and `5xx` statuses.
+///
+/// Delays grow exponentially from the base delay, `Retry-After` headers take precedence.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Number of attempts after the first one.
+    pub max_retries: u32,
+
+    /// Delay before the first retry, doubled for every next one.
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates policy repeating failed requests up to `max_retries` times.
+    pub fn new(max_retries: u32, base_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            base_delay,
+        }
+    }
+
+    /// Whether the response status is worth retrying.
+    pub(crate) fn retries_status(status: StatusCode) -> bool {
+        status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+    }
+
+    /// Delay before the retry following the given failed attempt, counted from zero.
+    pub(crate) fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| self.base_delay * 2u32.saturating_pow(attempt))
+            .min(MAX_DELAY)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Three retries starting one second apart.
+    fn default() -> Self {
+        Self::new(3, Duration::from_secs(1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RetryPolicy;
+    use crate::api::v2::endpoint::ApiEndpoint;
+    use crate::testing::{Fault, MockServer};
+    use serde_json::json;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn backs_off_exponentially() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(100));
+        assert_eq!(policy.delay(0, None), Duration::from_millis(100));
+        assert_eq!(policy.delay(3, None), Duration::from_millis(800));
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(2))),
+            Duration::from_secs(2)
+        );
+        assert_eq!(policy.delay(20, None), Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors() {
+        let server = MockServer::start().unwrap();
+        server.add_resource(
+            "berry-firmness",
+            json!({ "id": 1, "name": "very-soft", "berries": [], "names": [] }),
+        );
+        let policy = RetryPolicy::new(2, Duration::from_millis(10));
+        let client = server.client().unwrap().with_retry(policy);
+
+        server.add_fault_times(Fault::Status(503), 2);
+        let firmness = client.berry_firmnesses().get_by_id(1).await.unwrap();
+        assert_eq!(firmness.name, "very-soft");
+        assert_eq!(server.requests().len(), 3);
+
+        server.add_fault_times(Fault::Status(500), 3);
+        assert!(client.berry_firmnesses().get_by_id(1).await.is_err());
+
+        // Client errors are not transient.
+        assert!(client.berry_firmnesses().get_by_id(2).await.is_err());
+        assert_eq!(server.requests().len(), 7);
+    }
+
+    #[tokio::test]
+    async fn waits_as_long_as_retry_after_asks() {
+        let server = MockServer::start().unwrap();
+        server.add_resource(
+            "berry-firmness",
+            json!({ "id": 1, "name": "very-soft", "berries": [], "names": [] }),
+        );
+        let policy = RetryPolicy::new(1, Duration::from_millis(10));
+        let client = server.client().unwrap().with_retry(policy);
+
+        // MockServer asks to retry `429 Too Many Requests` after a second.
+        server.add_fault_times(Fault::Status(429), 1);
+        let started = Instant::now();
+        client.berry_firmnesses().get_by_id(1).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(server.requests().len(), 2);
+    }
+}
@@ -0,0 +1,408 @@
+//! Decoding of responses into models, tracking where the API and the models disagree.
+//!
+//! See `DecodeMode` for how strictly responses are checked.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::Result;
+
+/// Upper bound of fields defaulted in a single response, guarding against endless repairs.
+const MAX_REPAIRS: usize = 1024;
+
+/// How responses are turned into models.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DecodeMode {
+    /// Unknown fields are ignored and missing or null fields are errors.
+    #[default]
+    Normal,
+
+    /// Like `Normal`, but unknown fields are recorded in the drift report.
+    Strict,
+
+    /// Missing or null fields get default values, e.g. `""`, `0` or `[]`.
+    /// Both defaulted and unknown fields are recorded in the drift report.
+    Lenient,
+}
+
+/// Differences between the API responses and the models, by model name.
+/// Fields are given as paths like `flavors[].flavor.name`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct DriftReport {
+    /// Fields returned by the API that the models do not cover.
+    pub unknown_fields: BTreeMap<String, BTreeSet<String>>,
+
+    /// Fields missing or null in the responses, replaced by default values.
+    pub defaulted_fields: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl DriftReport {
+    /// Whether the responses matched the models.
+    pub fn is_empty(&self) -> bool {
+        self.unknown_fields.is_empty() && self.defaulted_fields.is_empty()
+    }
+
+    /// Adds the fields of the other report.
+    pub fn merge(&mut self, other: DriftReport) {
+        for (model, paths) in other.unknown_fields {
+            self.unknown_fields.entry(model).or_default().extend(paths);
+        }
+        for (model, paths) in other.defaulted_fields {
+            self.defaulted_fields
+                .entry(model)
+                .or_default()
+                .extend(paths);
+        }
+    }
+}
+
+impl fmt::Display for DriftReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (title, fields) in [
+            ("Unknown fields", &self.unknown_fields),
+            ("Defaulted fields", &self.defaulted_fields),
+        ]
+        .iter()
+        {
+            for (model, paths) in fields.iter() {
+                for path in paths.iter() {
+                    writeln!(f, "{}: {}.{}", title, model, path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Deserializes the response body into the model `T`, updating the drift report.
+pub fn decode<T: DeserializeOwned>(
+    body: &str,
+    mode: DecodeMode,
+    report: &mut DriftReport,
+) -> Result<T> {
+    let value = match mode {
+        DecodeMode::Normal => return Ok(serde_json::from_str(body)?),
+        DecodeMode::Strict => serde_json::from_str(body)?,
+        DecodeMode::Lenient => {
+            let mut value = serde_json::from_str(body)?;
+            for path in repair::<T>(&mut value)? {
+                report
+                    .defaulted_fields
+                    .entry(model_name::<T>())
+                    .or_default()
+                    .insert(path);
+            }
+            value
+        }
+    };
+
+    let mut unknown = BTreeSet::new();
+    let model = serde_ignored::deserialize(&value, |path| {
+        unknown.insert(ignored_path(&path));
+    })?;
+    if !unknown.is_empty() {
+        report
+            .unknown_fields
+            .entry(model_name::<T>())
+            .or_default()
+            .extend(unknown);
+    }
+    Ok(model)
+}
+
+/// Fills missing and null fields of the value until it deserializes into `T`.
+/// Returns paths of the defaulted fields.
+///
+/// Serde does not describe the expected types up front, so every pass deserializes the value
+/// and fixes the field its error names, using the messages matched by `missing_field` and
+/// `null_default`. The cost grows with the square of the defaulted fields, which stay few
+/// in practice; the `pins_serde_messages` test fails if serde changes the wording.
+fn repair<T: DeserializeOwned>(value: &mut Value) -> Result<BTreeSet<String>> {
+    let mut repaired = BTreeSet::new();
+    for _ in 0..MAX_REPAIRS {
+        let error = match serde_path_to_error::deserialize::<_, T>(&*value) {
+            Ok(_) => return Ok(repaired),
+            Err(error) => error,
+        };
+
+        let message = error.inner().to_string();
+        let mut segments: Vec<_> = error.path().iter().cloned().collect();
+        let mut path = segments_path(segments.iter());
+        let fixed = if let Some(field) = missing_field(&message) {
+            // Null is enough for optional fields; other types are fixed on the next pass.
+            path = join(&path, field);
+            segments.push(serde_path_to_error::Segment::Map {
+                key: field.to_string(),
+            });
+            set(value, &segments, Value::Null)
+        } else if let Some(default) = null_default(&message) {
+            set(value, &segments, default)
+        } else {
+            false
+        };
+
+        if !fixed {
+            return Err(error.into_inner().into());
+        }
+        repaired.insert(path);
+    }
+    Ok(repaired)
+}
+
+/// Field name of the `missing field `name`` error message.
+fn missing_field(message: &str) -> Option<&str> {
+    message.strip_prefix("missing field `")?.split('`').next()
+}
+
+/// Default value of the type expected instead of null, per the `invalid type: null` message.
+fn null_default(message: &str) -> Option<Value> {
+    let expected = message
+        .strip_prefix("invalid type: null, expected ")?
+        .split(" at line")
+        .next()?;
+
+    match expected {
+        "a string" => Some(Value::String(String::new())),
+        "a boolean" => Some(Value::Bool(false)),
+        "a sequence" => Some(Value::Array(Vec::new())),
+        "a map" => Some(Value::Object(Default::default())),
+        "f32" | "f64" => Some(0.0.into()),
+        e if e.starts_with("struct ") => Some(Value::Object(Default::default())),
+        e if e.starts_with('i') || e.starts_with('u') => Some(0.into()),
+        _ => None,
+    }
+}
+
+/// Replaces the value at the path, creating the last object key if needed.
+fn set(value: &mut Value, path: &[serde_path_to_error::Segment], new: Value) -> bool {
+    use serde_path_to_error::Segment;
+
+    let (last, parents) = match path.split_last() {
+        Some(split) => split,
+        None => return false,
+    };
+
+    let mut current = value;
+    for segment in parents {
+        current = match (segment, current) {
+            (Segment::Seq { index }, Value::Array(items)) => match items.get_mut(*index) {
+                Some(item) => item,
+                None => return false,
+            },
+            (Segment::Map { key }, Value::Object(map)) => match map.get_mut(key) {
+                Some(item) => item,
+                None => return false,
+            },
+            _ => return false,
+        };
+    }
+
+    match (last, current) {
+        (Segment::Seq { index }, Value::Array(items)) if *index < items.len() => {
+            items[*index] = new;
+            true
+        }
+        (Segment::Map { key }, Value::Object(map)) => {
+            map.insert(key.clone(), new);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Path like `flavors[].flavor` of the deserialization error.
+fn segments_path<'a, I: Iterator<Item = &'a serde_path_to_error::Segment>>(segments: I) -> String {
+    use serde_path_to_error::Segment;
+
+    let mut path = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Seq { .. } => path.push_str("[]"),
+            Segment::Map { key } => path = join(&path, key),
+            Segment::Enum { variant } => path = join(&path, variant),
+            Segment::Unknown => path = join(&path, "?"),
+        }
+    }
+    path
+}
+
+/// Path like `flavors[].flavor` of the ignored field.
+fn ignored_path(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, .. } => ignored_path(parent) + "[]",
+        Path::Map { parent, key } => join(&ignored_path(parent), key),
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => ignored_path(parent),
+    }
+}
+
+fn join(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
+
+/// Short name of the model type, e.g. `Berry`.
+fn model_name<T>() -> String {
+    let name = std::any::type_name::<T>();
+    let base = name.split('<').next().unwrap_or(name);
+    base.rsplit("::").next().unwrap_or(base).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode, missing_field, null_default, DecodeMode, DriftReport};
+    use crate::api::v2::endpoint::ApiEndpoint;
+    use crate::models::v2::berry::BerryFirmness;
+    use crate::models::v2::pokemon::Nature;
+    use crate::testing::MockServer;
+    use serde_json::json;
+
+    fn firmness() -> serde_json::Value {
+        json!({
+            "id": 2,
+            "name": "soft",
+            "berries": [{ "name": "cheri", "url": "", "sprite": "cheri.png" }],
+            "names": [],
+            "color": "red"
+        })
+    }
+
+    #[test]
+    fn reports_unknown_fields_in_strict_mode() {
+        let body = firmness().to_string();
+
+        let mut report = DriftReport::default();
+        decode::<BerryFirmness>(&body, DecodeMode::Normal, &mut report).unwrap();
+        assert!(report.is_empty());
+
+        let firmness: BerryFirmness = decode(&body, DecodeMode::Strict, &mut report).unwrap();
+        assert_eq!(firmness.berries[0].name, "cheri");
+        let unknown: Vec<&str> = report.unknown_fields["BerryFirmness"]
+            .iter()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(unknown, vec!["berries[].sprite", "color"]);
+        assert_eq!(
+            report.to_string(),
+            "Unknown fields: BerryFirmness.berries[].sprite\nUnknown fields: BerryFirmness.color\n"
+        );
+    }
+
+    #[test]
+    fn defaults_missing_fields_in_lenient_mode() {
+        let body = json!({
+            "id": 1,
+            "name": null,
+            "increased_stat": null,
+            "likes_flavor": { "name": "spicy" },
+            "pokeathlon_stat_changes": [{ "max_change": 1, "pokeathlon_stat": null }],
+            "names": null
+        })
+        .to_string();
+
+        let mut report = DriftReport::default();
+        assert!(decode::<Nature>(&body, DecodeMode::Strict, &mut report).is_err());
+
+        let nature: Nature = decode(&body, DecodeMode::Lenient, &mut report).unwrap();
+        assert_eq!(nature.name, "");
+        assert!(nature.increased_stat.is_none());
+        assert!(nature.decreased_stat.is_none());
+        assert_eq!(nature.likes_flavor.unwrap().url, "");
+        assert_eq!(nature.pokeathlon_stat_changes[0].pokeathlon_stat.name, "");
+        assert!(nature.move_battle_style_preferences.is_empty());
+
+        let defaulted: Vec<&str> = report.defaulted_fields["Nature"]
+            .iter()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(
+            defaulted,
+            vec![
+                "likes_flavor.url",
+                "move_battle_style_preferences",
+                "name",
+                "names",
+                "pokeathlon_stat_changes[].pokeathlon_stat",
+                "pokeathlon_stat_changes[].pokeathlon_stat.name",
+                "pokeathlon_stat_changes[].pokeathlon_stat.url",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn collects_client_drift() {
+        let server = MockServer::start().unwrap();
+        server.add_resource("berry-firmness", firmness());
+
+        let client = server
+            .client()
+            .unwrap()
+            .with_decode_mode(DecodeMode::Strict);
+        client.berry_firmnesses().get_by_id(2).await.unwrap();
+
+        let report = client.drift_report();
+        assert_eq!(report.unknown_fields["BerryFirmness"].len(), 2);
+        assert!(report.defaulted_fields.is_empty());
+    }
+
+    #[test]
+    fn pins_serde_messages() {
+        use serde::Deserialize;
+        use std::collections::BTreeMap;
+
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct Nested {
+            name: String,
+        }
+
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct Fields {
+            text: String,
+            flag: bool,
+            list: Vec<i32>,
+            map: BTreeMap<String, i32>,
+            float: f64,
+            int: i32,
+            unsigned: u32,
+            nested: Nested,
+        }
+
+        let valid = json!({
+            "text": "", "flag": false, "list": [], "map": {}, "float": 0.0,
+            "int": 0, "unsigned": 0, "nested": { "name": "" }
+        });
+        let message = |value: &serde_json::Value| {
+            serde_path_to_error::deserialize::<_, Fields>(value)
+                .err()
+                .map(|error| error.inner().to_string())
+                .unwrap_or_default()
+        };
+
+        for (field, default) in valid.as_object().unwrap().iter() {
+            let mut null = valid.clone();
+            null[field] = serde_json::Value::Null;
+            let default = match field.as_str() {
+                "nested" => json!({}),
+                _ => default.clone(),
+            };
+            assert_eq!(null_default(&message(&null)), Some(default), "{}", field);
+
+            let mut missing = valid.clone();
+            missing.as_object_mut().unwrap().remove(field);
+            assert_eq!(missing_field(&message(&missing)), Some(field.as_str()));
+        }
+    }
+}
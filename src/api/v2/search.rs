@@ -0,0 +1,291 @@
+use std::cmp::Ordering;
+
+use crate::api::v2::key::ResourceKey;
+use crate::models::v2::common::Name;
+use crate::models::v2::resource::{NamedResource, NamedResourceList};
+
+/// How the query matched a term of the resource.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    /// The term equals the query.
+    Exact,
+
+    /// The term starts with the query.
+    Prefix,
+
+    /// The term contains the query.
+    Substring,
+
+    /// The term differs from the query by the given number of edits.
+    Fuzzy(usize),
+}
+
+/// Resource found by the search.
+#[derive(Clone, Debug)]
+pub struct SearchHit {
+    /// The found resource.
+    pub resource: NamedResource,
+
+    /// The term the query matched, either the resource name or one of its localized names.
+    pub term: String,
+
+    /// How the query matched the term.
+    pub kind: MatchKind,
+}
+
+/// Searchable resource with its normalized terms.
+#[derive(Clone, Debug)]
+struct Entry {
+    resource: NamedResource,
+    terms: Vec<String>,
+}
+
+/// Index of resource names supporting prefix, substring and edit-distance matching.
+/// Names are compared as PokeAPI slugs, so case, accents and punctuation do not matter.
+#[derive(Clone, Debug, Default)]
+pub struct SearchIndex {
+    entries: Vec<Entry>,
+}
+
+impl SearchIndex {
+    /// Builds index of the resources of the list.
+    pub fn new(list: NamedResourceList) -> Self {
+        Self::from_resources(list.results)
+    }
+
+    /// Builds index of the resources.
+    pub fn from_resources(resources: Vec<NamedResource>) -> Self {
+        Self {
+            entries: resources
+                .into_iter()
+                .map(|resource| Entry {
+                    terms: vec![ResourceKey::slug(&resource.name)],
+                    resource,
+                })
+                .collect(),
+        }
+    }
+
+    /// Adds localized names of the resource with the url, so it can be found by them too.
+    pub fn add_names(&mut self, url: &str, names: &[Name]) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.resource.url == url) {
+            for name in names {
+                let term = ResourceKey::slug(&name.name);
+                if !term.is_empty() && !entry.terms.contains(&term) {
+                    entry.terms.push(term);
+                }
+            }
+        }
+    }
+
+    /// Indexed resources.
+    pub fn resources(&self) -> impl Iterator<Item = &NamedResource> {
+        self.entries.iter().map(|entry| &entry.resource)
+    }
+
+    /// Number of indexed resources.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the index has no resources.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Finds resources matching the query, best matches first.
+    /// Exact matches go before prefix ones, then substrings, then typos by the number of edits.
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let query = ResourceKey::slug(query);
+        if query.is_empty() {
+            return vec![];
+        }
+
+        let max_distance = (query.chars().count() / 2).clamp(1, 3);
+        let mut hits: Vec<SearchHit> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .terms
+                    .iter()
+                    .filter_map(|term| {
+                        match_term(&query, term, max_distance).map(|kind| (kind, term))
+                    })
+                    .min_by(|a, b| a.0.cmp(&b.0).then(a.1.len().cmp(&b.1.len())))
+                    .map(|(kind, term)| SearchHit {
+                        resource: entry.resource.clone(),
+                        term: term.clone(),
+                        kind,
+                    })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| match a.kind.cmp(&b.kind) {
+            Ordering::Equal => a
+                .term
+                .len()
+                .cmp(&b.term.len())
+                .then_with(|| a.resource.name.cmp(&b.resource.name)),
+            ordering => ordering,
+        });
+
+        hits
+    }
+}
+
+fn match_term(query: &str, term: &str, max_distance: usize) -> Option<MatchKind> {
+    if term == query {
+        Some(MatchKind::Exact)
+    } else if term.starts_with(query) {
+        Some(MatchKind::Prefix)
+    } else if term.contains(query) {
+        Some(MatchKind::Substring)
+    } else {
+        Some(edit_distance(query, term))
+            .filter(|distance| *distance <= max_distance)
+            .map(MatchKind::Fuzzy)
+    }
+}
+
+/// Number of insertions, deletions, substitutions and transpositions of adjacent characters
+/// needed to turn one string into another (optimal string alignment distance).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+
+            rows[i][j] = distance;
+        }
+    }
+
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::{edit_distance, MatchKind, SearchIndex};
+    use crate::api::v2::endpoint::ApiEndpoint;
+    use crate::models::v2::common::Name;
+    use crate::models::v2::resource::NamedResource;
+    use crate::testing::MockServer;
+    use serde_json::json;
+
+    fn resource(name: &str, id: usize) -> NamedResource {
+        NamedResource {
+            name: name.into(),
+            url: format!("https://pokeapi.co/api/v2/berry/{}/", id),
+        }
+    }
+
+    fn names(hits: Vec<super::SearchHit>) -> Vec<String> {
+        hits.into_iter().map(|hit| hit.resource.name).collect()
+    }
+
+    #[test]
+    fn computes_edit_distance() {
+        assert_eq!(edit_distance("cheri", "cheri"), 0);
+        assert_eq!(edit_distance("chry", "cheri"), 2);
+        assert_eq!(edit_distance("pehca", "pecha"), 1);
+        assert_eq!(edit_distance("", "lum"), 3);
+    }
+
+    #[test]
+    fn ranks_matches() {
+        let index = SearchIndex::from_resources(vec![
+            resource("cheri", 1),
+            resource("chesto", 2),
+            resource("pecha", 3),
+            resource("rawst", 4),
+            resource("lum", 9),
+            resource("chilan", 68),
+        ]);
+
+        let hits = index.search("chry");
+        assert_eq!(hits[0].resource.name, "cheri");
+        assert_eq!(hits[0].kind, MatchKind::Fuzzy(2));
+
+        assert_eq!(names(index.search("Che")), vec!["cheri", "chesto"]);
+        assert_eq!(index.search("lum")[0].kind, MatchKind::Exact);
+        assert_eq!(names(index.search("ech")), vec!["pecha"]);
+        assert_eq!(index.search("ech")[0].kind, MatchKind::Substring);
+        assert_eq!(names(index.search("rwast")), vec!["rawst"]);
+        assert!(index.search("xyzzy").is_empty());
+        assert!(index.search("").is_empty());
+    }
+
+    #[test]
+    fn searches_localized_names() {
+        let mut index = SearchIndex::from_resources(vec![NamedResource {
+            name: "super-hard".into(),
+            url: "https://pokeapi.co/api/v2/berry-firmness/5/".into(),
+        }]);
+        index.add_names(
+            "https://pokeapi.co/api/v2/berry-firmness/5/",
+            &[Name {
+                name: "Steinhart".into(),
+                language: NamedResource {
+                    name: "de".into(),
+                    url: "https://pokeapi.co/api/v2/language/6/".into(),
+                },
+            }],
+        );
+
+        let hits = index.search("steinhrt");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].resource.name, "super-hard");
+        assert_eq!(hits[0].term, "steinhart");
+    }
+
+    #[tokio::test]
+    async fn endpoint_search() {
+        let server = MockServer::start().unwrap();
+        for (id, name) in ["cheri", "chesto", "pecha"].iter().enumerate() {
+            server.add_resource("berry", json!({ "id": id + 1, "name": name }));
+        }
+        server.add_resource(
+            "berry-firmness",
+            json!({
+                "id": 5,
+                "name": "super-hard",
+                "berries": [],
+                "names": [{ "name": "Durissima", "language": { "name": "it", "url": "/api/v2/language/8/" } }]
+            }),
+        );
+
+        let client = server.client().unwrap();
+        let hits = client.berries().search("chry").await.unwrap();
+        assert_eq!(hits[0].resource.name, "cheri");
+
+        let hits = client.berries().search("pech").await.unwrap();
+        assert_eq!(hits[0].resource.name, "pecha");
+
+        // The list is fetched once and the index is cached in the client.
+        assert_eq!(server.requests().len(), 1);
+
+        let hits = client
+            .berry_firmnesses()
+            .search_localized("durisima")
+            .await
+            .unwrap();
+        assert_eq!(hits[0].resource.name, "super-hard");
+    }
+}
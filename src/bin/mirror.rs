@@ -0,0 +1,74 @@
+//! Dumps the whole PokeAPI to the local directory using the `api-data` layout.
+//!
+//! Usage: `pokeapi-mirror <output-dir> [--host <url>] [--interval <milliseconds>]`
+
+use std::env;
+use std::process;
+use std::time::Duration;
+
+use pokemon_api::api::v2::client::ApiClient;
+use pokemon_api::mirror::Mirror;
+
+/// Default interval between two requests, in milliseconds.
+const DEFAULT_INTERVAL: u64 = 100;
+
+fn usage() -> ! {
+    eprintln!("Usage: pokeapi-mirror <output-dir> [--host <url>] [--interval <milliseconds>]");
+    process::exit(2)
+}
+
+#[tokio::main(basic_scheduler)]
+async fn main() {
+    let mut args = env::args().skip(1);
+    let mut root = None;
+    let mut host = String::new();
+    let mut interval = DEFAULT_INTERVAL;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--host" => host = args.next().unwrap_or_else(|| usage()),
+            "--interval" => {
+                interval = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            "-h" | "--help" => usage(),
+            _ if root.is_none() => root = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let root = root.unwrap_or_else(|| usage());
+    let client = match ApiClient::new() {
+        Ok(client) => client.with_rate_limit(Duration::from_millis(interval)),
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1)
+        }
+    };
+
+    let mirror = Mirror::new(client, root)
+        .with_host(host)
+        .on_progress(|progress| {
+            eprintln!(
+                "[{}] {}/{} {}{}",
+                progress.endpoint,
+                progress.processed,
+                progress.total,
+                progress.url,
+                if progress.skipped { " (skipped)" } else { "" }
+            )
+        });
+
+    match mirror.run().await {
+        Ok(stats) => eprintln!(
+            "Done: {} fetched, {} already present",
+            stats.fetched, stats.skipped
+        ),
+        Err(err) => {
+            eprintln!("Mirroring failed: {}", err);
+            process::exit(1)
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Command line tool for querying PokeAPI.
+//!
+//! Run `pokeapi --help` for the list of commands.
+
+use std::process;
+
+use pokemon_api::cli;
+
+#[tokio::main(basic_scheduler)]
+async fn main() {
+    let matches = cli::app().get_matches();
+
+    match cli::run(&matches).await {
+        Ok(output) => println!("{}", output.trim_end()),
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1)
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Serves a PokeAPI compatible API from local data.
+//!
+//! Usage: `pokeapi-server (--dir <mirror-dir> | --cache-dir <dir> | --sqlite <file>) [--addr <host:port>]`
+
+use std::env;
+use std::process;
+
+use pokemon_api::api::v2::cache::FileCache;
+use pokemon_api::server::{CacheBackend, Server, SnapshotBackend, DEFAULT_ADDR};
+use pokemon_api::Result;
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: pokeapi-server (--dir <mirror-dir> | --cache-dir <dir> | --sqlite <file>) [--addr <host:port>]"
+    );
+    process::exit(2)
+}
+
+/// Starts the server with the backend given by the option.
+fn start(addr: &str, option: &str, path: &str) -> Result<Server> {
+    match option {
+        "--dir" => Server::start(addr, SnapshotBackend::open(path)?),
+        "--cache-dir" => Server::start(addr, CacheBackend::new(FileCache::new(path))),
+        #[cfg(feature = "sqlite")]
+        "--sqlite" => Server::start(addr, pokemon_api::sqlite::SqliteStore::open(path)?),
+        #[cfg(not(feature = "sqlite"))]
+        "--sqlite" => {
+            eprintln!("pokeapi-server was built without the `sqlite` feature");
+            process::exit(2)
+        }
+        _ => usage(),
+    }
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let mut addr = DEFAULT_ADDR.to_string();
+    let mut source = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--addr" => addr = args.next().unwrap_or_else(|| usage()),
+            "--dir" | "--cache-dir" | "--sqlite" if source.is_none() => {
+                source = Some((arg, args.next().unwrap_or_else(|| usage())))
+            }
+            _ => usage(),
+        }
+    }
+
+    let (option, path) = source.unwrap_or_else(|| usage());
+    match start(&addr, &option, &path) {
+        Ok(server) => {
+            eprintln!("Serving {}", server.base_url());
+            server.join();
+        }
+        Err(err) => {
+            eprintln!("Starting the server failed: {}", err);
+            process::exit(1)
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! Breeding compatibility, offspring species and inherited egg moves.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::api::v2::client::ApiClient;
+use crate::api::v2::endpoint::fetch_all;
+use crate::mirror::load_all;
+use crate::models::v2::pokemon::{Pokemon, PokemonSpecies};
+use crate::{Error, Result};
+
+/// Name of the species breeding with any species that can breed at all.
+pub const DITTO: &str = "ditto";
+
+/// Name of the egg group of species unable to breed, called Undiscovered in the games.
+pub const NO_EGGS: &str = "no-eggs";
+
+/// Name of the learn method of egg moves.
+pub const EGG_LEARN_METHOD: &str = "egg";
+
+/// Baby species hatching only when a parent holds the incense, with the incense item name.
+/// Without the incense the egg hatches into the next stage.
+pub const INCENSE_BABIES: [(&str, &str); 9] = [
+    ("wynaut", "lax-incense"),
+    ("azurill", "sea-incense"),
+    ("budew", "rose-incense"),
+    ("chingling", "pure-incense"),
+    ("bonsly", "rock-incense"),
+    ("mime-jr", "odd-incense"),
+    ("happiny", "luck-incense"),
+    ("munchlax", "full-incense"),
+    ("mantyke", "wave-incense"),
+];
+
+/// Species hatching from eggs of another species instead of their own family.
+const SPECIAL_OFFSPRING: [(&str, &str); 1] = [("manaphy", "phione")];
+
+/// Species whose eggs may also hatch into the paired species, with the paired species name.
+pub const COUNTERPART_OFFSPRING: [(&str, &str); 2] =
+    [("nidoran-f", "nidoran-m"), ("illumise", "volbeat")];
+
+/// Gender of a parent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gender {
+    Female,
+    Male,
+    Genderless,
+}
+
+/// Pokémon left at the Day Care.
+#[derive(Clone, Copy, Debug)]
+pub struct Parent<'a> {
+    pub species: &'a PokemonSpecies,
+    pub gender: Gender,
+}
+
+impl<'a> Parent<'a> {
+    /// Creates parent of the species with the gender.
+    pub fn new(species: &'a PokemonSpecies, gender: Gender) -> Self {
+        Self { species, gender }
+    }
+
+    fn is_ditto(&self) -> bool {
+        self.species.name == DITTO
+    }
+
+    fn in_egg_group(&self, group: &str) -> bool {
+        self.species.egg_groups.iter().any(|g| g.name == group)
+    }
+
+    /// Checks the gender is possible for the species according to its `gender_rate`.
+    fn check(&self) -> Result<()> {
+        let possible = match (self.species.gender_rate, self.gender) {
+            (rate, Gender::Genderless) => rate < 0,
+            (rate, Gender::Female) => rate > 0,
+            (rate, Gender::Male) => (0..8).contains(&rate),
+        };
+
+        if possible {
+            Ok(())
+        } else {
+            Err(Error::InvalidArgument(format!(
+                "species `{}` cannot be {:?}",
+                self.species.name, self.gender
+            )))
+        }
+    }
+}
+
+/// Whether the two parents can produce an egg.
+///
+/// Species of the `no-eggs` group never breed. Ditto breeds with every other species,
+/// genderless ones included, but not with another Ditto. Other parents need opposite genders
+/// and a shared egg group.
+pub fn can_breed(a: &Parent, b: &Parent) -> Result<bool> {
+    a.check()?;
+    b.check()?;
+
+    if a.in_egg_group(NO_EGGS) || b.in_egg_group(NO_EGGS) {
+        return Ok(false);
+    }
+
+    Ok(match (a.is_ditto(), b.is_ditto()) {
+        (true, true) => false,
+        (true, false) | (false, true) => true,
+        (false, false) => {
+            let opposite = matches!(
+                (a.gender, b.gender),
+                (Gender::Female, Gender::Male) | (Gender::Male, Gender::Female)
+            );
+            opposite
+                && a.species
+                    .egg_groups
+                    .iter()
+                    .any(|group| b.in_egg_group(&group.name))
+        }
+    })
+}
+
+/// Names of the egg moves the offspring can inherit from the parent in the version group,
+/// i.e. its egg moves the parent can learn there in any way.
+pub fn egg_moves(offspring: &Pokemon, parent: &Pokemon, version_group: &str) -> Vec<String> {
+    let learns = |pokemon: &Pokemon, name: &str, method: Option<&str>| {
+        pokemon.moves.iter().any(|m| {
+            m.r#move.name == name
+                && m.version_group_details.iter().any(|details| {
+                    details.version_group.name == version_group
+                        && method.is_none_or(|method| details.move_learn_method.name == method)
+                })
+        })
+    };
+
+    let mut moves: Vec<String> = offspring
+        .moves
+        .iter()
+        .map(|m| &m.r#move.name)
+        .filter(|name| learns(offspring, name, Some(EGG_LEARN_METHOD)))
+        .filter(|name| learns(parent, name, None))
+        .cloned()
+        .collect();
+
+    moves.sort();
+    moves.dedup();
+    moves
+}
+
+/// Species data needed to follow evolution families back to the species hatching from eggs.
+#[derive(Clone, Debug, Default)]
+pub struct Breeding {
+    species: HashMap<String, PokemonSpecies>,
+}
+
+impl Breeding {
+    /// Creates breeding rules over the species.
+    pub fn new(species: Vec<PokemonSpecies>) -> Self {
+        Self {
+            species: species.into_iter().map(|s| (s.name.clone(), s)).collect(),
+        }
+    }
+
+    /// Creates breeding rules over the species fetched with the client.
+    /// Responses go through the client cache, if it has one.
+    pub async fn from_api(client: &ApiClient) -> Result<Self> {
+        Ok(Self::new(fetch_all(&client.pokemon_species()).await?))
+    }
+
+    /// Creates breeding rules over the species stored in the directory by `Mirror`.
+    pub fn from_snapshot<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        Ok(Self::new(load_all(dir.as_ref(), "pokemon-species")?))
+    }
+
+    /// Species with the name.
+    pub fn species(&self, name: &str) -> Option<&PokemonSpecies> {
+        self.species.get(name)
+    }
+
+    /// Name of the species hatching from the egg of the two parents.
+    ///
+    /// The egg takes the species of the mother, or of the partner of Ditto, and hatches
+    /// into the first stage of its family. Babies listed in `INCENSE_BABIES` hatch only when
+    /// a parent holds the `incense`, otherwise the egg hatches into the next stage.
+    ///
+    /// Eggs of the species in `COUNTERPART_OFFSPRING` hatch into either species of the pair
+    /// at random, only the first one is returned here, see `possible_offspring`.
+    pub fn offspring(&self, a: &Parent, b: &Parent, incense: Option<&str>) -> Result<String> {
+        if !can_breed(a, b)? {
+            return Err(Error::InvalidArgument(format!(
+                "`{}` and `{}` cannot breed",
+                a.species.name, b.species.name
+            )));
+        }
+
+        let mother = if a.is_ditto() {
+            b
+        } else if b.is_ditto() || a.gender == Gender::Female {
+            a
+        } else {
+            b
+        };
+
+        if let Some((_, offspring)) = SPECIAL_OFFSPRING
+            .iter()
+            .find(|(parent, _)| *parent == mother.species.name)
+        {
+            return Ok(offspring.to_string());
+        }
+
+        let mut family = vec![mother.species];
+        while let Some(ref previous) = family[family.len() - 1].evolves_from_species {
+            let previous = self.species(&previous.name).ok_or_else(|| {
+                Error::InvalidArgument(format!("species `{}` is not loaded", previous.name))
+            })?;
+            // A family longer than the loaded species goes around in a cycle.
+            if family.len() > self.species.len() {
+                return Err(Error::InvalidArgument(format!(
+                    "evolution family of `{}` has a cycle",
+                    mother.species.name
+                )));
+            }
+            family.push(previous);
+        }
+
+        let first = family[family.len() - 1];
+        let needs_incense = INCENSE_BABIES
+            .iter()
+            .find(|(baby, _)| *baby == first.name)
+            .map(|(_, required)| Some(*required) != incense)
+            .unwrap_or(false);
+
+        if needs_incense && family.len() > 1 {
+            Ok(family[family.len() - 2].name.clone())
+        } else {
+            Ok(first.name.clone())
+        }
+    }
+
+    /// Names of every species that may hatch from the egg of the two parents,
+    /// the species given by `offspring` first.
+    pub fn possible_offspring(
+        &self,
+        a: &Parent,
+        b: &Parent,
+        incense: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let offspring = self.offspring(a, b, incense)?;
+        let counterpart = COUNTERPART_OFFSPRING
+            .iter()
+            .find(|(species, _)| *species == offspring)
+            .map(|(_, counterpart)| counterpart.to_string());
+
+        Ok(std::iter::once(offspring).chain(counterpart).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{can_breed, egg_moves, Breeding, Gender, Parent};
+    use crate::models::v2::pokemon::{Pokemon, PokemonSpecies};
+    use serde_json::{json, Value};
+
+    fn reference(name: &str) -> Value {
+        json!({ "name": name, "url": "" })
+    }
+
+    fn species(
+        name: &str,
+        egg_groups: &[&str],
+        gender_rate: i32,
+        evolves_from: Option<&str>,
+        is_baby: bool,
+    ) -> PokemonSpecies {
+        let egg_groups: Vec<_> = egg_groups.iter().map(|g| reference(g)).collect();
+        serde_json::from_value(json!({
+            "id": 1,
+            "name": name,
+            "order": 1,
+            "gender_rate": gender_rate,
+            "capture_rate": 45,
+            "base_happiness": 70,
+            "is_baby": is_baby,
+            "hatch_counter": 20,
+            "has_gender_differences": false,
+            "forms_switchable": false,
+            "growth_rate": reference("medium"),
+            "egg_groups": egg_groups,
+            "color": reference("blue"),
+            "shape": null,
+            "evolves_from_species": evolves_from.map(reference),
+            "evolution_chain": { "url": "" },
+            "habitat": null,
+            "generation": reference("generation-i"),
+            "names": [],
+            "flavor_text_entries": [],
+            "form_descriptions": [],
+            "genera": [],
+            "varieties": []
+        }))
+        .unwrap()
+    }
+
+    fn breeding() -> Breeding {
+        Breeding::new(vec![
+            species("ditto", &["ditto"], -1, None, false),
+            species("azurill", &["no-eggs"], 6, None, true),
+            species("marill", &["water1", "fairy"], 4, Some("azurill"), false),
+            species("azumarill", &["water1", "fairy"], 4, Some("marill"), false),
+            species("pichu", &["no-eggs"], 4, None, true),
+            species("pikachu", &["ground", "fairy"], 4, Some("pichu"), false),
+            species("magnemite", &["mineral"], -1, None, false),
+            species("mewtwo", &["no-eggs"], -1, None, false),
+            species("tauros", &["ground"], 0, None, false),
+            species("nidoran-f", &["monster", "ground"], 8, None, false),
+            species("nidoran-m", &["monster", "ground"], 0, None, false),
+            species("illumise", &["bug", "human-like"], 8, None, false),
+            species("volbeat", &["bug", "human-like"], 0, None, false),
+        ])
+    }
+
+    fn parent<'a>(breeding: &'a Breeding, name: &str, gender: Gender) -> Parent<'a> {
+        Parent::new(breeding.species(name).unwrap(), gender)
+    }
+
+    #[test]
+    fn checks_compatibility() {
+        let b = breeding();
+
+        let azumarill = parent(&b, "azumarill", Gender::Female);
+        let pikachu = parent(&b, "pikachu", Gender::Male);
+        assert!(can_breed(&azumarill, &pikachu).unwrap());
+        assert!(!can_breed(&azumarill, &parent(&b, "marill", Gender::Female)).unwrap());
+        assert!(!can_breed(&azumarill, &parent(&b, "tauros", Gender::Male)).unwrap());
+
+        let ditto = parent(&b, "ditto", Gender::Genderless);
+        let magnemite = parent(&b, "magnemite", Gender::Genderless);
+        assert!(can_breed(&ditto, &magnemite).unwrap());
+        assert!(!can_breed(&magnemite, &parent(&b, "pikachu", Gender::Female)).unwrap());
+        assert!(!can_breed(&ditto, &ditto).unwrap());
+        assert!(!can_breed(&ditto, &parent(&b, "mewtwo", Gender::Genderless)).unwrap());
+        assert!(!can_breed(&parent(&b, "azurill", Gender::Female), &pikachu).unwrap());
+
+        assert!(can_breed(&parent(&b, "tauros", Gender::Female), &ditto).is_err());
+    }
+
+    #[test]
+    fn finds_offspring() {
+        let b = breeding();
+        let ditto = parent(&b, "ditto", Gender::Genderless);
+
+        let azumarill = parent(&b, "azumarill", Gender::Female);
+        let pikachu = parent(&b, "pikachu", Gender::Male);
+        assert_eq!(b.offspring(&azumarill, &pikachu, None).unwrap(), "marill");
+        assert_eq!(
+            b.offspring(&pikachu, &azumarill, Some("sea-incense"))
+                .unwrap(),
+            "azurill"
+        );
+        assert_eq!(b.offspring(&pikachu, &ditto, None).unwrap(), "pichu");
+        assert_eq!(
+            b.offspring(&ditto, &parent(&b, "magnemite", Gender::Genderless), None)
+                .unwrap(),
+            "magnemite"
+        );
+        assert!(b.offspring(&ditto, &ditto, None).is_err());
+    }
+
+    #[test]
+    fn rejects_evolution_cycles() {
+        let b = Breeding::new(vec![
+            species("ditto", &["ditto"], -1, None, false),
+            species("pichu", &["ground"], 4, Some("raichu"), false),
+            species("pikachu", &["ground"], 4, Some("pichu"), false),
+            species("raichu", &["ground"], 4, Some("pikachu"), false),
+        ]);
+
+        let raichu = parent(&b, "raichu", Gender::Female);
+        let ditto = parent(&b, "ditto", Gender::Genderless);
+        assert!(b.offspring(&raichu, &ditto, None).is_err());
+    }
+
+    #[test]
+    fn finds_counterpart_offspring() {
+        let b = breeding();
+
+        let nidoran = parent(&b, "nidoran-f", Gender::Female);
+        let volbeat = parent(&b, "volbeat", Gender::Male);
+        assert_eq!(
+            b.possible_offspring(&nidoran, &parent(&b, "nidoran-m", Gender::Male), None)
+                .unwrap(),
+            vec!["nidoran-f", "nidoran-m"]
+        );
+        assert_eq!(
+            b.possible_offspring(&volbeat, &parent(&b, "illumise", Gender::Female), None)
+                .unwrap(),
+            vec!["illumise", "volbeat"]
+        );
+        assert_eq!(
+            b.possible_offspring(&volbeat, &parent(&b, "ditto", Gender::Genderless), None)
+                .unwrap(),
+            vec!["volbeat"]
+        );
+    }
+
+    fn pokemon(name: &str, moves: &[(&str, &str, &str)]) -> Pokemon {
+        let moves: Vec<_> = moves
+            .iter()
+            .map(|(name, method, version_group)| {
+                json!({
+                    "move": reference(name),
+                    "version_group_details": [{
+                        "move_learn_method": reference(method),
+                        "version_group": reference(version_group),
+                        "level_learned_at": 0
+                    }]
+                })
+            })
+            .collect();
+
+        serde_json::from_value(json!({
+            "id": 1,
+            "name": name,
+            "base_experience": null,
+            "height": 1,
+            "is_default": true,
+            "order": 1,
+            "weight": 1,
+            "abilities": [],
+            "forms": [],
+            "game_indices": [],
+            "held_items": [],
+            "location_area_encounters": "",
+            "moves": moves,
+            "sprites": {},
+            "species": reference(name),
+            "stats": [],
+            "types": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn finds_egg_moves() {
+        let pichu = pokemon(
+            "pichu",
+            &[
+                ("volt-tackle", "egg", "emerald"),
+                ("encore", "egg", "emerald"),
+                ("wish", "egg", "emerald"),
+                ("charm", "level-up", "emerald"),
+            ],
+        );
+        let togepi = pokemon(
+            "togepi",
+            &[
+                ("encore", "level-up", "emerald"),
+                ("wish", "egg", "emerald"),
+                ("charm", "level-up", "emerald"),
+                ("volt-tackle", "level-up", "platinum"),
+            ],
+        );
+
+        assert_eq!(
+            egg_moves(&pichu, &togepi, "emerald"),
+            vec!["encore", "wish"]
+        );
+        assert!(egg_moves(&pichu, &togepi, "platinum").is_empty());
+    }
+}
@@ -0,0 +1,454 @@
+//! Pokéblock (generation III) and Poffin (generation IV) cooking simulator.
+//!
+//! Both treats are cooked the same way: flavor potencies of the berries are added up,
+//! every flavor is weakened by the next one (spicy by dry, … sour by spicy),
+//! positive flavors lose one point per negative flavor and negative ones drop to zero.
+//! The level is the strongest flavor and the feel is the average smoothness of the berries
+//! minus their number. Blending speed and cooking time are assumed to be neutral.
+
+use serde::Serialize;
+
+use crate::calc::flavor::{potency, FLAVORS};
+use crate::models::v2::berry::Berry;
+use crate::models::v2::common::Integer;
+use crate::{Error, Result};
+
+/// Most berries cooked at once.
+pub const MAX_BERRIES: usize = 4;
+
+/// Highest level of a Poffin.
+pub const MAX_POFFIN_LEVEL: Integer = 100;
+
+/// Level from which Pokéblocks are gold and Poffins are mild.
+const HIGH_LEVEL: Integer = 50;
+
+/// Contest conditions raised by treats, each tied to a flavor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContestStat {
+    Cool,
+    Beauty,
+    Cute,
+    Smart,
+    Tough,
+}
+
+impl ContestStat {
+    /// Every condition in the order of their flavors.
+    pub const ALL: [ContestStat; 5] = [
+        ContestStat::Cool,
+        ContestStat::Beauty,
+        ContestStat::Cute,
+        ContestStat::Smart,
+        ContestStat::Tough,
+    ];
+
+    /// Name of the flavor raising the condition.
+    pub fn flavor(self) -> &'static str {
+        FLAVORS[self as usize]
+    }
+
+    /// Condition raised by the flavor.
+    pub fn from_flavor(flavor: &str) -> Option<Self> {
+        FLAVORS
+            .iter()
+            .position(|f| *f == flavor)
+            .map(|index| Self::ALL[index])
+    }
+}
+
+/// Flavors, level and feel of a cooked treat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Taste {
+    /// Strength of every flavor, in the order of `FLAVORS`.
+    pub flavors: [Integer; 5],
+
+    /// Strength of the strongest flavor.
+    pub level: Integer,
+
+    /// How much the treat fills up the Pokémon, lower is better.
+    pub feel: Integer,
+}
+
+impl Taste {
+    /// Strength of the flavor raising the condition.
+    pub fn condition(&self, stat: ContestStat) -> Integer {
+        self.flavors[stat as usize]
+    }
+
+    /// Names of the flavors present, strongest first.
+    pub fn present_flavors(&self) -> Vec<&'static str> {
+        let mut present: Vec<usize> = (0..FLAVORS.len())
+            .filter(|i| self.flavors[*i] > 0)
+            .collect();
+        present.sort_by(|a, b| self.flavors[*b].cmp(&self.flavors[*a]));
+        present.into_iter().map(|i| FLAVORS[i]).collect()
+    }
+}
+
+/// Color of a Pokéblock, telling its flavors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum PokeblockColor {
+    Red,
+    Blue,
+    Pink,
+    Green,
+    Yellow,
+    Purple,
+    Indigo,
+    Brown,
+    LiteBlue,
+    Olive,
+    Gray,
+    Black,
+    Gold,
+}
+
+/// Pokéblock made in a Berry Blender.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Pokeblock {
+    pub color: PokeblockColor,
+    pub taste: Taste,
+}
+
+/// Kind of a Poffin, telling its flavors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum PoffinKind {
+    /// Made of the same berry twice or without any flavor left.
+    Foul,
+
+    /// Has four or more flavors.
+    Overripe,
+
+    /// Has three flavors.
+    Rich,
+
+    /// Has level 50 or more.
+    Mild,
+
+    /// Has a single flavor.
+    Single(&'static str),
+
+    /// Has two flavors, the strongest one first.
+    Double(&'static str, &'static str),
+}
+
+/// Poffin cooked in a Poffin Case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Poffin {
+    pub kind: PoffinKind,
+    pub taste: Taste,
+}
+
+/// Blends a Pokéblock of two to four berries using the generation III rules.
+pub fn blend_pokeblock(berries: &[&Berry]) -> Result<Pokeblock> {
+    if berries.len() < 2 {
+        return Err(Error::InvalidArgument(
+            "a Berry Blender needs two to four berries".into(),
+        ));
+    }
+
+    let taste = cook(berries)?;
+    let present = taste.present_flavors();
+    let color = if has_duplicates(berries) || present.is_empty() {
+        PokeblockColor::Black
+    } else if taste.level >= HIGH_LEVEL {
+        PokeblockColor::Gold
+    } else if present.len() >= 3 {
+        PokeblockColor::Gray
+    } else {
+        let single = [
+            PokeblockColor::Red,
+            PokeblockColor::Blue,
+            PokeblockColor::Pink,
+            PokeblockColor::Green,
+            PokeblockColor::Yellow,
+        ];
+        let double = [
+            PokeblockColor::Purple,
+            PokeblockColor::Indigo,
+            PokeblockColor::Brown,
+            PokeblockColor::LiteBlue,
+            PokeblockColor::Olive,
+        ];
+        let strongest = FLAVORS.iter().position(|f| *f == present[0]).unwrap();
+        if present.len() == 1 {
+            single[strongest]
+        } else {
+            double[strongest]
+        }
+    };
+
+    Ok(Pokeblock { color, taste })
+}
+
+/// Cooks a Poffin of one to four berries using the generation IV rules.
+pub fn cook_poffin(berries: &[&Berry]) -> Result<Poffin> {
+    let mut taste = cook(berries)?;
+    taste.level = taste.level.min(MAX_POFFIN_LEVEL);
+
+    let present = taste.present_flavors();
+    let kind = if has_duplicates(berries) || present.is_empty() {
+        PoffinKind::Foul
+    } else if present.len() >= 4 {
+        PoffinKind::Overripe
+    } else if present.len() == 3 {
+        PoffinKind::Rich
+    } else if taste.level >= HIGH_LEVEL {
+        PoffinKind::Mild
+    } else if present.len() == 2 {
+        PoffinKind::Double(present[0], present[1])
+    } else {
+        PoffinKind::Single(present[0])
+    };
+
+    Ok(Poffin { kind, taste })
+}
+
+fn cook(berries: &[&Berry]) -> Result<Taste> {
+    if berries.is_empty() || berries.len() > MAX_BERRIES {
+        return Err(Error::InvalidArgument(format!(
+            "expected one to {} berries, got {}",
+            MAX_BERRIES,
+            berries.len()
+        )));
+    }
+
+    let mut sums = [0; 5];
+    for (sum, flavor) in sums.iter_mut().zip(FLAVORS.iter()) {
+        *sum = berries.iter().map(|berry| potency(berry, flavor)).sum();
+    }
+
+    let mut flavors = [0; 5];
+    for (i, flavor) in flavors.iter_mut().enumerate() {
+        *flavor = sums[i] - sums[(i + 1) % sums.len()];
+    }
+
+    let negative = flavors.iter().filter(|f| **f < 0).count() as Integer;
+    for flavor in flavors.iter_mut() {
+        *flavor = (*flavor - negative).max(0);
+    }
+
+    let count = berries.len() as Integer;
+    let smoothness: Integer = berries.iter().map(|berry| berry.smoothness).sum();
+
+    Ok(Taste {
+        flavors,
+        level: flavors.iter().copied().max().unwrap_or(0),
+        feel: (smoothness / count - count).max(0),
+    })
+}
+
+fn has_duplicates(berries: &[&Berry]) -> bool {
+    berries
+        .iter()
+        .enumerate()
+        .any(|(i, berry)| berries[..i].iter().any(|other| other.name == berry.name))
+}
+
+/// Treat the recipe search is looking for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Treat {
+    Pokeblock,
+    Poffin,
+}
+
+/// Berries giving the best treat for a condition, with the taste of the treat.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Recipe {
+    pub berries: Vec<String>,
+    pub taste: Taste,
+}
+
+/// Finds the combination of distinct berries raising the condition the most.
+///
+/// Recipes are compared by the strength of the condition flavor, then by the lower feel
+/// and then by fewer berries. `max_berries` limits the size of the combinations.
+pub fn best_recipe(
+    berries: &[Berry],
+    stat: ContestStat,
+    treat: Treat,
+    max_berries: usize,
+) -> Option<Recipe> {
+    let min_berries = match treat {
+        Treat::Pokeblock => 2,
+        Treat::Poffin => 1,
+    };
+    let max_berries = max_berries.min(MAX_BERRIES);
+
+    let mut best: Option<(Vec<usize>, Taste)> = None;
+    let mut combination = Vec::with_capacity(max_berries);
+    let mut visit = |indices: &[usize]| {
+        if indices.len() < min_berries {
+            return;
+        }
+        let chosen: Vec<&Berry> = indices.iter().map(|i| &berries[*i]).collect();
+        let taste = match treat {
+            Treat::Pokeblock => blend_pokeblock(&chosen).map(|block| block.taste),
+            Treat::Poffin => cook_poffin(&chosen).map(|poffin| poffin.taste),
+        };
+        let taste = match taste {
+            Ok(taste) if taste.condition(stat) > 0 => taste,
+            _ => return,
+        };
+
+        let better = match best {
+            None => true,
+            Some((ref current, ref current_taste)) => {
+                (
+                    taste.condition(stat),
+                    -taste.feel,
+                    -(indices.len() as isize),
+                ) > (
+                    current_taste.condition(stat),
+                    -current_taste.feel,
+                    -(current.len() as isize),
+                )
+            }
+        };
+        if better {
+            best = Some((indices.to_vec(), taste));
+        }
+    };
+
+    combinations(berries.len(), max_berries, 0, &mut combination, &mut visit);
+
+    best.map(|(indices, taste)| Recipe {
+        berries: indices.iter().map(|i| berries[*i].name.clone()).collect(),
+        taste,
+    })
+}
+
+/// Calls the function with every combination of up to `max` indices below `len`.
+fn combinations<F: FnMut(&[usize])>(
+    len: usize,
+    max: usize,
+    start: usize,
+    combination: &mut Vec<usize>,
+    visit: &mut F,
+) {
+    visit(combination);
+    if combination.len() == max {
+        return;
+    }
+
+    for i in start..len {
+        combination.push(i);
+        combinations(len, max, i + 1, combination, visit);
+        combination.pop();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        best_recipe, blend_pokeblock, cook_poffin, ContestStat, PoffinKind, PokeblockColor, Treat,
+    };
+    use crate::calc::flavor::FLAVORS;
+    use crate::models::v2::berry::Berry;
+    use crate::models::v2::common::Integer;
+    use serde_json::json;
+
+    fn berry(name: &str, potencies: [Integer; 5], smoothness: Integer) -> Berry {
+        let flavors: Vec<_> = FLAVORS
+            .iter()
+            .zip(potencies.iter())
+            .map(|(flavor, potency)| {
+                json!({ "potency": potency, "flavor": { "name": flavor, "url": "" } })
+            })
+            .collect();
+
+        serde_json::from_value(json!({
+            "id": 1,
+            "name": name,
+            "growth_time": 3,
+            "max_harvest": 5,
+            "natural_gift_power": 60,
+            "size": 20,
+            "smoothness": smoothness,
+            "soil_dryness": 15,
+            "firmness": { "name": "soft", "url": "" },
+            "flavors": flavors,
+            "item": { "name": format!("{}-berry", name), "url": "" },
+            "natural_gift_type": { "name": "fire", "url": "" }
+        }))
+        .unwrap()
+    }
+
+    fn berries() -> Vec<Berry> {
+        vec![
+            berry("cheri", [10, 0, 0, 0, 0], 25),
+            berry("chesto", [0, 10, 0, 0, 0], 25),
+            berry("pecha", [0, 0, 10, 0, 0], 25),
+            berry("rawst", [0, 0, 0, 10, 0], 25),
+            berry("aspear", [0, 0, 0, 0, 10], 25),
+            berry("spelon", [30, 10, 0, 0, 0], 35),
+            berry("tamato", [20, 10, 0, 0, 0], 30),
+        ]
+    }
+
+    #[test]
+    fn maps_flavors_to_conditions() {
+        assert_eq!(ContestStat::Cool.flavor(), "spicy");
+        assert_eq!(ContestStat::Tough.flavor(), "sour");
+        assert_eq!(ContestStat::from_flavor("sweet"), Some(ContestStat::Cute));
+        assert_eq!(ContestStat::from_flavor("umami"), None);
+    }
+
+    #[test]
+    fn blends_pokeblocks() {
+        let berries = berries();
+
+        // Cheri and Pecha: spicy 10, dry -10, sweet 10, bitter 0, sour -10.
+        let block = blend_pokeblock(&[&berries[0], &berries[2]]).unwrap();
+        assert_eq!(block.taste.flavors, [8, 0, 8, 0, 0]);
+        assert_eq!(block.taste.level, 8);
+        assert_eq!(block.taste.feel, 23);
+        assert_eq!(block.color, PokeblockColor::Purple);
+
+        let block = blend_pokeblock(&[&berries[0], &berries[1]]).unwrap();
+        assert_eq!(block.taste.flavors, [0, 9, 0, 0, 0]);
+        assert_eq!(block.color, PokeblockColor::Blue);
+
+        let block = blend_pokeblock(&[&berries[0], &berries[0]]).unwrap();
+        assert_eq!(block.color, PokeblockColor::Black);
+
+        assert!(blend_pokeblock(&[&berries[0]]).is_err());
+    }
+
+    #[test]
+    fn cooks_poffins() {
+        let berries = berries();
+
+        let poffin = cook_poffin(&[&berries[5]]).unwrap();
+        assert_eq!(poffin.taste.flavors, [19, 9, 0, 0, 0]);
+        assert_eq!(poffin.kind, PoffinKind::Double("spicy", "dry"));
+        assert_eq!(poffin.taste.feel, 34);
+
+        let all: Vec<&Berry> = berries[..4].iter().collect();
+        let poffin = cook_poffin(&all).unwrap();
+        assert_eq!(poffin.kind, PoffinKind::Single("bitter"));
+
+        let poffin = cook_poffin(&[&berries[1], &berries[1]]).unwrap();
+        assert_eq!(poffin.kind, PoffinKind::Foul);
+
+        let all: Vec<&Berry> = berries.iter().collect();
+        assert!(cook_poffin(&all).is_err());
+    }
+
+    #[test]
+    fn finds_best_recipe() {
+        let berries = berries();
+
+        let recipe = best_recipe(&berries, ContestStat::Cool, Treat::Poffin, 4).unwrap();
+        assert_eq!(recipe.berries, vec!["cheri", "pecha", "spelon", "tamato"]);
+        assert_eq!(recipe.taste.condition(ContestStat::Cool), 39);
+
+        let recipe = best_recipe(&berries, ContestStat::Cool, Treat::Poffin, 1).unwrap();
+        assert_eq!(recipe.berries, vec!["spelon"]);
+
+        let recipe = best_recipe(&berries, ContestStat::Tough, Treat::Pokeblock, 2).unwrap();
+        assert!(recipe.berries.contains(&"aspear".to_string()));
+        assert!(recipe.taste.condition(ContestStat::Tough) > 0);
+    }
+}
@@ -0,0 +1,512 @@
+//! Damage ranges and KO chances using the formula of generation V and later.
+//!
+//! Critical hits deal 2× damage in generation V and 1.5× since generation VI,
+//! see `DamageInput::with_generation`.
+
+use std::collections::BTreeMap;
+
+use crate::calc::stats::{Stat, Stats};
+use crate::calc::types::TypeChart;
+use crate::models::v2::moves::Move;
+use crate::models::v2::pokemon::Pokemon;
+use crate::{Error, Result};
+
+/// Number of random factors a hit can roll, from 85% to 100%.
+pub const ROLLS: u32 = 16;
+
+/// Generation whose damage formula is used by default.
+pub const LATEST_GENERATION: u32 = 9;
+
+/// Kind of damage a move deals, the name of the `move-damage-class` endpoint resource.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageClass {
+    /// Uses Attack against Defense.
+    Physical,
+
+    /// Uses Special Attack against Special Defense.
+    Special,
+
+    /// Deals no direct damage.
+    Status,
+}
+
+impl DamageClass {
+    /// Finds the damage class by the name of the `move-damage-class` endpoint resource.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "physical" => Some(DamageClass::Physical),
+            "special" => Some(DamageClass::Special),
+            "status" => Some(DamageClass::Status),
+            _ => None,
+        }
+    }
+}
+
+/// Weather affecting the damage of fire and water moves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Sun,
+    Rain,
+}
+
+impl Weather {
+    /// Multiplier of a move of the type, in 4096ths.
+    fn modifier(self, move_type: Option<&str>) -> u32 {
+        match (self, move_type) {
+            (Weather::Sun, Some("fire")) | (Weather::Rain, Some("water")) => 6144,
+            (Weather::Sun, Some("water")) | (Weather::Rain, Some("fire")) => 2048,
+            _ => 4096,
+        }
+    }
+}
+
+/// Everything the damage of a single hit depends on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DamageInput {
+    /// Level of the attacking Pokémon.
+    pub level: u32,
+
+    /// Stats of the attacking Pokémon.
+    pub attacker: Stats,
+
+    /// Stats of the defending Pokémon.
+    pub defender: Stats,
+
+    /// Base power of the move.
+    pub power: u32,
+
+    /// Damage class of the move, selecting the stats the damage is based on.
+    pub damage_class: DamageClass,
+
+    /// Name of the move type, needed for the weather to apply.
+    pub move_type: Option<String>,
+
+    /// Whether the move gets the same-type attack bonus.
+    pub stab: bool,
+
+    /// Type effectiveness multiplier against the defending Pokémon.
+    pub effectiveness: f64,
+
+    /// Weather on the field.
+    pub weather: Weather,
+
+    /// Whether the hit is critical.
+    pub critical: bool,
+
+    /// Generation of the games, 5 or later.
+    pub generation: u32,
+}
+
+impl DamageInput {
+    /// Creates input of a neutral hit without weather, STAB or critical hit.
+    pub fn new(
+        level: u32,
+        attacker: Stats,
+        defender: Stats,
+        power: u32,
+        damage_class: DamageClass,
+    ) -> Self {
+        Self {
+            level,
+            attacker,
+            defender,
+            power,
+            damage_class,
+            move_type: None,
+            stab: false,
+            effectiveness: 1.0,
+            weather: Weather::Clear,
+            critical: false,
+            generation: LATEST_GENERATION,
+        }
+    }
+
+    /// Creates input of the move used by one Pokémon against another.
+    /// STAB comes from the attacker types and the effectiveness from the chart.
+    pub fn from_move(
+        level: u32,
+        attacker: (&Pokemon, Stats),
+        defender: (&Pokemon, Stats),
+        used: &Move,
+        chart: &TypeChart,
+    ) -> Result<Self> {
+        let damage_class = DamageClass::from_name(&used.damage_class.name).ok_or_else(|| {
+            Error::InvalidArgument(format!("unknown damage class `{}`", used.damage_class.name))
+        })?;
+        let move_type = used.r#type.name.as_str();
+        let defender_types: Vec<&str> = defender
+            .0
+            .types
+            .iter()
+            .map(|t| t.r#type.name.as_str())
+            .collect();
+
+        Ok(Self {
+            move_type: Some(move_type.to_string()),
+            stab: attacker.0.types.iter().any(|t| t.r#type.name == move_type),
+            effectiveness: chart.multiplier(move_type, &defender_types)?,
+            ..Self::new(
+                level,
+                attacker.1,
+                defender.1,
+                used.power.unwrap_or(0) as u32,
+                damage_class,
+            )
+        })
+    }
+
+    /// Sets the move type.
+    pub fn with_move_type(mut self, move_type: &str) -> Self {
+        self.move_type = Some(move_type.to_string());
+        self
+    }
+
+    /// Sets the same-type attack bonus.
+    pub fn with_stab(mut self, stab: bool) -> Self {
+        self.stab = stab;
+        self
+    }
+
+    /// Sets the type effectiveness multiplier, e.g. `TypeChart::multiplier`.
+    pub fn with_effectiveness(mut self, effectiveness: f64) -> Self {
+        self.effectiveness = effectiveness;
+        self
+    }
+
+    /// Sets the weather.
+    pub fn with_weather(mut self, weather: Weather) -> Self {
+        self.weather = weather;
+        self
+    }
+
+    /// Sets whether the hit is critical.
+    pub fn with_critical(mut self, critical: bool) -> Self {
+        self.critical = critical;
+        self
+    }
+
+    /// Sets the generation of the games, 5 or later.
+    pub fn with_generation(mut self, generation: u32) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Multiplier of critical hits in 4096ths.
+    fn critical_modifier(&self) -> u32 {
+        if self.generation == 5 {
+            8192
+        } else {
+            6144
+        }
+    }
+
+    /// Computes the damage of every random factor.
+    pub fn calculate(&self) -> Result<DamageRange> {
+        if self.generation < 5 {
+            return Err(Error::InvalidArgument(format!(
+                "generation {} uses a different damage formula",
+                self.generation
+            )));
+        }
+        if !(1..=100).contains(&self.level) {
+            return Err(Error::InvalidArgument(format!(
+                "level {} is out of 1..=100",
+                self.level
+            )));
+        }
+        if self.damage_class == DamageClass::Status || self.power == 0 {
+            return Err(Error::InvalidArgument(
+                "status moves and moves without power deal no direct damage".into(),
+            ));
+        }
+
+        let (attack, defense) = match self.damage_class {
+            DamageClass::Physical => (Stat::Attack, Stat::Defense),
+            _ => (Stat::SpecialAttack, Stat::SpecialDefense),
+        };
+        let defense = self.defender.get(defense).max(1);
+
+        let mut base = (2 * self.level / 5 + 2) * self.power * self.attacker.get(attack) / defense;
+        base = base / 50 + 2;
+        base = apply(base, self.weather.modifier(self.move_type.as_deref()));
+        if self.critical {
+            base = apply(base, self.critical_modifier());
+        }
+
+        let rolls = (100 - ROLLS + 1..=100)
+            .map(|random| {
+                let mut damage = base * random / 100;
+                if self.stab {
+                    damage = apply(damage, 6144);
+                }
+                damage = (f64::from(damage) * self.effectiveness) as u32;
+                if self.effectiveness > 0.0 {
+                    damage.max(1)
+                } else {
+                    0
+                }
+            })
+            .collect();
+
+        Ok(DamageRange { rolls })
+    }
+}
+
+/// Applies the multiplier given in 4096ths, rounding halves down like the games do.
+fn apply(value: u32, modifier: u32) -> u32 {
+    (value * modifier + 2047) / 4096
+}
+
+/// Damage of a hit for every random factor, from the lowest to the highest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DamageRange {
+    rolls: Vec<u32>,
+}
+
+impl DamageRange {
+    /// Damage of every random factor.
+    pub fn rolls(&self) -> &[u32] {
+        &self.rolls
+    }
+
+    /// The lowest damage.
+    pub fn min(&self) -> u32 {
+        self.rolls[0]
+    }
+
+    /// The highest damage.
+    pub fn max(&self) -> u32 {
+        self.rolls[self.rolls.len() - 1]
+    }
+
+    /// Probability that a single hit knocks out a Pokémon with the HP left.
+    pub fn ko_probability(&self, hp: u32) -> f64 {
+        self.ko_probability_in(hp, 1)
+    }
+
+    /// Probability that the given number of hits knock out a Pokémon with the HP left.
+    pub fn ko_probability_in(&self, hp: u32, hits: u32) -> f64 {
+        if hp == 0 {
+            return 1.0;
+        }
+        if self.rolls.iter().all(|roll| *roll == 0) {
+            return 0.0;
+        }
+
+        // Chances of the damage dealt so far that did not knock out yet, by total damage,
+        // so the work follows the distinct totals rather than the HP.
+        let mut chances = BTreeMap::new();
+        chances.insert(0u32, 1.0);
+        let roll_chance = 1.0 / self.rolls.len() as f64;
+        let mut knocked_out = 0.0;
+
+        for _ in 0..hits {
+            if chances.is_empty() {
+                break;
+            }
+
+            let mut next = BTreeMap::new();
+            for (dealt, chance) in chances {
+                for roll in self.rolls.iter() {
+                    let total = dealt.saturating_add(*roll);
+                    if total >= hp {
+                        knocked_out += chance * roll_chance;
+                    } else {
+                        *next.entry(total).or_insert(0.0) += chance * roll_chance;
+                    }
+                }
+            }
+            chances = next;
+        }
+
+        knocked_out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DamageClass, DamageInput, Weather};
+    use crate::calc::stats::Stats;
+    use crate::calc::types::TypeChart;
+    use crate::models::v2::moves::Move;
+    use crate::models::v2::pokemon::{Pokemon, Type};
+    use serde_json::json;
+
+    fn attacker(attack: u32) -> Stats {
+        Stats {
+            attack,
+            special_attack: attack,
+            ..Stats::default()
+        }
+    }
+
+    fn defender(defense: u32) -> Stats {
+        Stats {
+            hp: 200,
+            defense,
+            special_defense: defense,
+            ..Stats::default()
+        }
+    }
+
+    fn pokemon(name: &str, types: &[&str]) -> Pokemon {
+        let types: Vec<_> = types
+            .iter()
+            .enumerate()
+            .map(|(slot, name)| json!({ "slot": slot + 1, "type": { "name": name, "url": "" } }))
+            .collect();
+
+        serde_json::from_value(json!({
+            "id": 1,
+            "name": name,
+            "base_experience": null,
+            "height": 1,
+            "is_default": true,
+            "order": 1,
+            "weight": 1,
+            "abilities": [],
+            "forms": [],
+            "game_indices": [],
+            "held_items": [],
+            "location_area_encounters": "",
+            "moves": [],
+            "sprites": {},
+            "species": { "name": name, "url": "" },
+            "stats": [],
+            "types": types
+        }))
+        .unwrap()
+    }
+
+    fn chart() -> TypeChart {
+        let new_type = |name: &str, double_damage_to: Vec<&str>| -> Type {
+            let targets: Vec<_> = double_damage_to
+                .iter()
+                .map(|name| json!({ "name": name, "url": "" }))
+                .collect();
+            serde_json::from_value(json!({
+                "id": 1,
+                "name": name,
+                "damage_relations": {
+                    "no_damage_to": [], "half_damage_to": [], "double_damage_to": targets,
+                    "no_damage_from": [], "half_damage_from": [], "double_damage_from": []
+                },
+                "past_damage_relations": [],
+                "game_indices": [],
+                "generation": { "name": "generation-i", "url": "" },
+                "move_damage_class": null,
+                "names": [],
+                "pokemon": [],
+                "moves": []
+            }))
+            .unwrap()
+        };
+
+        TypeChart::new(&[
+            new_type("ice", vec!["dragon", "ground"]),
+            new_type("dragon", vec![]),
+            new_type("ground", vec![]),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn computes_damage_range() {
+        // Level 75 Glaceon uses Ice Fang on Garchomp.
+        let range = DamageInput::new(75, attacker(123), defender(163), 65, DamageClass::Physical)
+            .with_stab(true)
+            .with_effectiveness(4.0)
+            .calculate()
+            .unwrap();
+
+        assert_eq!(range.rolls().len(), 16);
+        assert_eq!(range.min(), 168);
+        assert_eq!(range.max(), 196);
+    }
+
+    #[test]
+    fn applies_modifiers() {
+        let input = DamageInput::new(50, attacker(100), defender(100), 80, DamageClass::Special);
+        let neutral = input.calculate().unwrap();
+        assert_eq!((neutral.min(), neutral.max()), (31, 37));
+
+        let sun = input
+            .clone()
+            .with_move_type("fire")
+            .with_weather(Weather::Sun);
+        assert_eq!(sun.calculate().unwrap().max(), 55);
+
+        let rain = input
+            .clone()
+            .with_move_type("fire")
+            .with_weather(Weather::Rain);
+        assert_eq!(rain.calculate().unwrap().max(), 18);
+
+        let critical = input.clone().with_critical(true).calculate().unwrap();
+        assert_eq!(critical.max(), 55);
+        let critical = input.clone().with_critical(true).with_generation(5);
+        assert_eq!(critical.calculate().unwrap().max(), 74);
+        assert!(input.clone().with_generation(4).calculate().is_err());
+
+        let immune = input.clone().with_effectiveness(0.0).calculate().unwrap();
+        assert_eq!(immune.max(), 0);
+
+        assert!(
+            DamageInput::new(50, attacker(100), defender(100), 0, DamageClass::Status)
+                .calculate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn computes_ko_probability() {
+        let range = DamageInput::new(50, attacker(100), defender(100), 80, DamageClass::Special)
+            .calculate()
+            .unwrap();
+
+        assert_eq!(range.ko_probability(31), 1.0);
+        assert_eq!(range.ko_probability(38), 0.0);
+        assert_eq!(range.ko_probability(37), 1.0 / 16.0);
+        assert_eq!(range.ko_probability_in(62, 2), 1.0);
+        assert_eq!(range.ko_probability_in(75, 2), 0.0);
+        assert!(range.ko_probability_in(68, 2) > 0.4);
+        assert!(range.ko_probability_in(68, 2) < 0.6);
+        assert_eq!(range.ko_probability_in(u32::MAX, 3), 0.0);
+    }
+
+    #[test]
+    fn builds_input_from_models() {
+        let ice_fang: Move = serde_json::from_value(json!({
+            "id": 423,
+            "name": "ice-fang",
+            "accuracy": 95,
+            "effect_chance": 10,
+            "pp": 15,
+            "priority": 0,
+            "power": 65,
+            "damage_class": { "name": "physical", "url": "" },
+            "effect_entries": [],
+            "generation": { "name": "generation-iv", "url": "" },
+            "meta": null,
+            "names": [],
+            "target": { "name": "selected-pokemon", "url": "" },
+            "type": { "name": "ice", "url": "" }
+        }))
+        .unwrap();
+
+        let input = DamageInput::from_move(
+            75,
+            (&pokemon("glaceon", &["ice"]), attacker(123)),
+            (&pokemon("garchomp", &["dragon", "ground"]), defender(163)),
+            &ice_fang,
+            &chart(),
+        )
+        .unwrap();
+
+        assert!(input.stab);
+        assert_eq!(input.effectiveness, 4.0);
+        assert_eq!(input.calculate().unwrap().max(), 196);
+    }
+}
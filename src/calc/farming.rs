@@ -0,0 +1,354 @@
+//! Berry farming planner following the generation IV growth rules.
+//!
+//! A planted berry goes through four growth stages of `growth_time` hours each and is
+//! harvested when the last one ends. The soil loses `soil_dryness` percent of moisture
+//! every hour, so it has to be watered before it dries out to get the full `max_harvest`.
+
+use std::convert::TryFrom;
+
+use serde::Serialize;
+
+use crate::models::v2::berry::Berry;
+use crate::{Error, Result};
+
+/// Number of growth stages before berries can be picked.
+pub const GROWTH_STAGES: u32 = 4;
+
+/// Names of the stages a tree enters, in order.
+pub const STAGE_NAMES: [&str; GROWTH_STAGES as usize] =
+    ["sprouted", "taller", "flowering", "berries"];
+
+/// Default length of the plan, four days.
+pub const DEFAULT_HOURS: u32 = 96;
+
+/// Moisture of freshly watered soil, in percent.
+const FULL_MOISTURE: u32 = 100;
+
+/// Action happening on a plot at a given hour.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum FarmEvent {
+    /// The berry is planted into the plot.
+    Plant { hour: u32, plot: u32, berry: String },
+
+    /// The tree grows into the next stage.
+    Grow {
+        hour: u32,
+        plot: u32,
+        berry: String,
+        stage: &'static str,
+    },
+
+    /// The plot is watered.
+    Water { hour: u32, plot: u32 },
+
+    /// The berries are picked.
+    Harvest {
+        hour: u32,
+        plot: u32,
+        berry: String,
+        amount: u32,
+    },
+}
+
+impl FarmEvent {
+    /// Hour of the plan the event happens at.
+    pub fn hour(&self) -> u32 {
+        match self {
+            FarmEvent::Plant { hour, .. }
+            | FarmEvent::Grow { hour, .. }
+            | FarmEvent::Water { hour, .. }
+            | FarmEvent::Harvest { hour, .. } => *hour,
+        }
+    }
+
+    /// Plot the event happens on.
+    pub fn plot(&self) -> u32 {
+        match self {
+            FarmEvent::Plant { plot, .. }
+            | FarmEvent::Grow { plot, .. }
+            | FarmEvent::Water { plot, .. }
+            | FarmEvent::Harvest { plot, .. } => *plot,
+        }
+    }
+}
+
+/// Totals of one berry over the plan.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BerrySummary {
+    /// Name of the berry.
+    pub berry: String,
+
+    /// Number of plots the berry is grown on.
+    pub plots: u32,
+
+    /// Hours from planting to harvest.
+    pub hours_to_harvest: u32,
+
+    /// Hours between waterings keeping the soil moist.
+    pub watering_interval: u32,
+
+    /// Berries a single plot yields per hour.
+    pub yield_per_hour: f64,
+
+    /// Number of harvests within the plan.
+    pub harvests: u32,
+
+    /// Number of berries picked within the plan.
+    pub berries: u32,
+}
+
+/// Planting, watering and harvesting timeline of a farm.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FarmPlan {
+    /// Length of the plan in hours.
+    pub hours: u32,
+
+    /// Totals of every planned berry.
+    pub summary: Vec<BerrySummary>,
+
+    /// Events ordered by hour and plot.
+    pub timeline: Vec<FarmEvent>,
+}
+
+impl FarmPlan {
+    /// Serializes the plan into pretty JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Schedules berries over plots of soil.
+#[derive(Clone, Debug)]
+pub struct FarmPlanner<'a> {
+    berries: Vec<&'a Berry>,
+    plots: u32,
+    hours: u32,
+}
+
+impl<'a> FarmPlanner<'a> {
+    /// Creates planner assigning plots to the berries in turn,
+    /// so the first berries get one more plot when they do not divide evenly.
+    pub fn new(berries: &'a [Berry], plots: u32) -> Self {
+        Self {
+            berries: berries.iter().collect(),
+            plots,
+            hours: DEFAULT_HOURS,
+        }
+    }
+
+    /// Changes length of the plan.
+    pub fn with_hours(mut self, hours: u32) -> Self {
+        self.hours = hours;
+        self
+    }
+
+    /// Builds the plan. Every plot is replanted with the same berry right after the harvest.
+    pub fn plan(&self) -> Result<FarmPlan> {
+        if self.berries.is_empty() || self.plots == 0 {
+            return Err(Error::InvalidArgument(
+                "farm plan needs at least one berry and one plot".into(),
+            ));
+        }
+        let mut summary: Vec<BerrySummary> = self
+            .berries
+            .iter()
+            .map(|berry| {
+                let growth = hours_to_harvest(berry)?;
+                Ok(BerrySummary {
+                    berry: berry.name.clone(),
+                    plots: 0,
+                    hours_to_harvest: growth,
+                    watering_interval: watering_interval(berry),
+                    yield_per_hour: f64::from(berry.max_harvest) / f64::from(growth),
+                    harvests: 0,
+                    berries: 0,
+                })
+            })
+            .collect::<Result<_>>()?;
+        let mut timeline = Vec::new();
+
+        for plot in 0..self.plots {
+            let index = plot as usize % self.berries.len();
+            let berry = self.berries[index];
+            let growth = summary[index].hours_to_harvest;
+            let stage_hours = growth / GROWTH_STAGES;
+            let interval = summary[index].watering_interval;
+            summary[index].plots += 1;
+
+            let mut planted: u32 = 0;
+            while let Some(harvest) = planted.checked_add(growth).filter(|h| *h <= self.hours) {
+                timeline.push(FarmEvent::Plant {
+                    hour: planted,
+                    plot,
+                    berry: berry.name.clone(),
+                });
+                timeline.push(FarmEvent::Water {
+                    hour: planted,
+                    plot,
+                });
+
+                let mut watered = planted.saturating_add(interval);
+                while watered < harvest {
+                    timeline.push(FarmEvent::Water {
+                        hour: watered,
+                        plot,
+                    });
+                    watered = watered.saturating_add(interval);
+                }
+
+                for (stage, name) in STAGE_NAMES.iter().enumerate() {
+                    timeline.push(FarmEvent::Grow {
+                        hour: planted + stage_hours * (stage as u32 + 1),
+                        plot,
+                        berry: berry.name.clone(),
+                        stage: name,
+                    });
+                }
+
+                timeline.push(FarmEvent::Harvest {
+                    hour: harvest,
+                    plot,
+                    berry: berry.name.clone(),
+                    amount: berry.max_harvest as u32,
+                });
+                summary[index].harvests += 1;
+                summary[index].berries = summary[index]
+                    .berries
+                    .saturating_add(berry.max_harvest as u32);
+
+                planted = harvest;
+            }
+        }
+
+        // The sort is stable, so events of a plot at the same hour keep their logical order.
+        timeline.sort_by_key(|event| (event.hour(), event.plot()));
+        summary.retain(|s| s.plots > 0);
+
+        Ok(FarmPlan {
+            hours: self.hours,
+            summary,
+            timeline,
+        })
+    }
+}
+
+/// Hours from planting the berry to its harvest, an error for invalid growth data.
+fn hours_to_harvest(berry: &Berry) -> Result<u32> {
+    u32::try_from(berry.growth_time)
+        .ok()
+        .filter(|_| berry.growth_time > 0 && berry.soil_dryness >= 0 && berry.max_harvest >= 0)
+        .and_then(|hours| hours.checked_mul(GROWTH_STAGES))
+        .ok_or_else(|| {
+            Error::InvalidArgument(format!("berry `{}` has invalid growth data", berry.name))
+        })
+}
+
+/// Hours the watered soil under the berry stays moist.
+pub fn watering_interval(berry: &Berry) -> u32 {
+    match berry.soil_dryness {
+        dryness if dryness <= 0 => u32::MAX,
+        dryness => (FULL_MOISTURE / dryness as u32).max(1),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{watering_interval, FarmEvent, FarmPlanner};
+    use crate::models::v2::berry::Berry;
+    use serde_json::json;
+
+    fn berry(name: &str, growth_time: i32, max_harvest: i32, soil_dryness: i32) -> Berry {
+        serde_json::from_value(json!({
+            "id": 1,
+            "name": name,
+            "growth_time": growth_time,
+            "max_harvest": max_harvest,
+            "natural_gift_power": 60,
+            "size": 20,
+            "smoothness": 25,
+            "soil_dryness": soil_dryness,
+            "firmness": { "name": "soft", "url": "" },
+            "flavors": [],
+            "item": { "name": format!("{}-berry", name), "url": "" },
+            "natural_gift_type": { "name": "fire", "url": "" }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn schedules_harvests() {
+        let berries = vec![berry("cheri", 3, 5, 15), berry("sitrus", 8, 5, 7)];
+        let plan = FarmPlanner::new(&berries, 3).with_hours(48).plan().unwrap();
+
+        let cheri = &plan.summary[0];
+        assert_eq!(cheri.plots, 2);
+        assert_eq!(cheri.hours_to_harvest, 12);
+        assert_eq!(cheri.watering_interval, 6);
+        assert_eq!(cheri.harvests, 8);
+        assert_eq!(cheri.berries, 40);
+        assert!((cheri.yield_per_hour - 5.0 / 12.0).abs() < 1e-9);
+
+        let sitrus = &plan.summary[1];
+        assert_eq!(sitrus.plots, 1);
+        assert_eq!(sitrus.harvests, 1);
+        assert_eq!(sitrus.watering_interval, 14);
+
+        assert!(plan
+            .timeline
+            .windows(2)
+            .all(|w| (w[0].hour(), w[0].plot()) <= (w[1].hour(), w[1].plot())));
+        assert_eq!(
+            plan.timeline[0],
+            FarmEvent::Plant {
+                hour: 0,
+                plot: 0,
+                berry: "cheri".into()
+            }
+        );
+
+        let waterings = plan
+            .timeline
+            .iter()
+            .filter(|e| matches!(e, FarmEvent::Water { plot: 1, .. }))
+            .count();
+        assert_eq!(waterings, 3);
+    }
+
+    #[test]
+    fn serializes_timeline() {
+        let berries = vec![berry("cheri", 3, 5, 15)];
+        let plan = FarmPlanner::new(&berries, 1).with_hours(12).plan().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&plan.to_json().unwrap()).unwrap();
+
+        assert_eq!(value["hours"], 12);
+        assert_eq!(value["timeline"][0]["action"], "plant");
+        assert_eq!(value["timeline"][2]["action"], "grow");
+        assert_eq!(value["timeline"][2]["stage"], "sprouted");
+        assert_eq!(value["summary"][0]["berries"], 5);
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        let berries = vec![berry("cheri", 3, 5, 15)];
+        assert!(FarmPlanner::new(&berries, 0).plan().is_err());
+        assert!(FarmPlanner::new(&[], 4).plan().is_err());
+        assert!(FarmPlanner::new(&[berry("broken", 0, 5, 15)], 1)
+            .plan()
+            .is_err());
+        assert!(FarmPlanner::new(&[berry("negative", -3, 5, 15)], 1)
+            .plan()
+            .is_err());
+        assert!(FarmPlanner::new(&[berry("endless", i32::MAX, 5, 15)], 1)
+            .plan()
+            .is_err());
+
+        // Plans ending at the last representable hour still stop.
+        let plan = FarmPlanner::new(&[berry("slow", i32::MAX / 4, 5, 0)], 1)
+            .with_hours(u32::MAX)
+            .plan()
+            .unwrap();
+        assert_eq!(plan.summary[0].harvests, 2);
+        assert_eq!(watering_interval(&berry("dry", 3, 5, 0)), u32::MAX);
+    }
+}
@@ -0,0 +1,213 @@
+//! Berry flavor profiles and how Pokémon of a nature like them.
+
+use std::cmp::Ordering;
+
+use crate::models::v2::berry::{Berry, BerryFlavorMap};
+use crate::models::v2::common::Integer;
+use crate::models::v2::pokemon::Nature;
+
+/// Names of the flavors in the order the API lists them.
+pub const FLAVORS: [&str; 5] = ["spicy", "dry", "sweet", "bitter", "sour"];
+
+/// Berries restoring HP at low health that confuse Pokémon disliking their flavor,
+/// with the flavor each of them has.
+pub const CONFUSION_BERRIES: [(&str, &str); 5] = [
+    ("figy", "spicy"),
+    ("wiki", "dry"),
+    ("mago", "sweet"),
+    ("aguav", "bitter"),
+    ("iapapa", "sour"),
+];
+
+/// Potency of the flavor of the berry, `0` if the berry lacks the flavor.
+pub fn potency(berry: &Berry, flavor: &str) -> Integer {
+    berry
+        .flavors
+        .iter()
+        .find(|f| f.flavor.name == flavor)
+        .map(|f| f.potency)
+        .unwrap_or(0)
+}
+
+/// Name of the most potent flavor of the berry, `None` if it has no flavor at all.
+/// Ties go to the flavor listed first by the API.
+pub fn dominant_flavor(berry: &Berry) -> Option<&str> {
+    berry
+        .flavors
+        .iter()
+        .filter(|f| f.potency > 0)
+        .fold(None, |best: Option<&BerryFlavorMap>, f| match best {
+            Some(best) if best.potency >= f.potency => Some(best),
+            _ => Some(f),
+        })
+        .map(|f| f.flavor.name.as_str())
+}
+
+/// Berry ranked for a nature.
+#[derive(Clone, Debug)]
+pub struct RankedBerry<'a> {
+    /// The ranked berry.
+    pub berry: &'a Berry,
+
+    /// Potency of the liked flavor minus potency of the disliked one.
+    pub score: Integer,
+}
+
+/// Ranks the berries by how much a Pokémon of the nature likes them, favourite first.
+///
+/// The score is the potency of the liked flavor minus the potency of the disliked one.
+/// Neutral natures like every flavor the same, so the berries keep their order.
+pub fn rank_for_nature<'a>(berries: &'a [Berry], nature: &Nature) -> Vec<RankedBerry<'a>> {
+    let liked = nature.likes_flavor.as_ref().map(|f| f.name.as_str());
+    let hated = nature.hates_flavor.as_ref().map(|f| f.name.as_str());
+
+    let mut ranked: Vec<RankedBerry> = berries
+        .iter()
+        .map(|berry| RankedBerry {
+            berry,
+            score: match (liked, hated) {
+                (Some(liked), Some(hated)) if liked != hated => {
+                    potency(berry, liked) - potency(berry, hated)
+                }
+                _ => 0,
+            },
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| match b.score.cmp(&a.score) {
+        Ordering::Equal => a.berry.id.cmp(&b.berry.id),
+        ordering => ordering,
+    });
+
+    ranked
+}
+
+/// Whether eating the berry confuses a Pokémon of the nature.
+pub fn confuses(berry: &Berry, nature: &Nature) -> bool {
+    let liked = nature.likes_flavor.as_ref().map(|f| f.name.as_str());
+    let hated = match nature.hates_flavor {
+        Some(ref flavor) if liked != Some(flavor.name.as_str()) => &flavor.name,
+        _ => return false,
+    };
+
+    CONFUSION_BERRIES
+        .iter()
+        .any(|(name, flavor)| berry.name == *name && flavor == hated)
+}
+
+/// Berries that confuse a Pokémon of the nature.
+pub fn confusing_berries<'a>(berries: &'a [Berry], nature: &Nature) -> Vec<&'a Berry> {
+    berries
+        .iter()
+        .filter(|berry| confuses(berry, nature))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{confusing_berries, dominant_flavor, potency, rank_for_nature};
+    use crate::models::v2::berry::Berry;
+    use crate::models::v2::common::Integer;
+    use crate::models::v2::pokemon::Nature;
+    use serde_json::json;
+
+    fn berry(id: usize, name: &str, potencies: [Integer; 5]) -> Berry {
+        let flavors: Vec<_> = super::FLAVORS
+            .iter()
+            .zip(potencies.iter())
+            .map(|(flavor, potency)| {
+                json!({ "potency": potency, "flavor": { "name": flavor, "url": "" } })
+            })
+            .collect();
+
+        serde_json::from_value(json!({
+            "id": id,
+            "name": name,
+            "growth_time": 3,
+            "max_harvest": 5,
+            "natural_gift_power": 60,
+            "size": 20,
+            "smoothness": 25,
+            "soil_dryness": 15,
+            "firmness": { "name": "soft", "url": "" },
+            "flavors": flavors,
+            "item": { "name": format!("{}-berry", name), "url": "" },
+            "natural_gift_type": { "name": "fire", "url": "" }
+        }))
+        .unwrap()
+    }
+
+    fn nature(name: &str, likes: &str, hates: &str) -> Nature {
+        serde_json::from_value(json!({
+            "id": 1,
+            "name": name,
+            "decreased_stat": null,
+            "increased_stat": null,
+            "hates_flavor": { "name": hates, "url": "" },
+            "likes_flavor": { "name": likes, "url": "" },
+            "pokeathlon_stat_changes": [],
+            "move_battle_style_preferences": [],
+            "names": []
+        }))
+        .unwrap()
+    }
+
+    fn berries() -> Vec<Berry> {
+        vec![
+            berry(1, "cheri", [10, 0, 0, 0, 0]),
+            berry(2, "chesto", [0, 10, 0, 0, 0]),
+            berry(3, "pecha", [0, 0, 10, 0, 0]),
+            berry(14, "spelon", [30, 10, 0, 0, 0]),
+            berry(15, "figy", [15, 0, 0, 0, 0]),
+            berry(16, "wiki", [0, 15, 0, 0, 0]),
+            berry(17, "mago", [0, 0, 15, 0, 0]),
+        ]
+    }
+
+    #[test]
+    fn finds_dominant_flavor() {
+        let berries = berries();
+        assert_eq!(dominant_flavor(&berries[3]), Some("spicy"));
+        assert_eq!(potency(&berries[3], "dry"), 10);
+        assert_eq!(
+            dominant_flavor(&berry(20, "tie", [0, 10, 10, 0, 0])),
+            Some("dry")
+        );
+        assert_eq!(dominant_flavor(&berry(21, "bland", [0; 5])), None);
+    }
+
+    #[test]
+    fn ranks_berries_for_nature() {
+        let berries = berries();
+        let adamant = nature("adamant", "spicy", "dry");
+
+        let ranked: Vec<&str> = rank_for_nature(&berries, &adamant)
+            .iter()
+            .map(|r| r.berry.name.as_str())
+            .collect();
+        assert_eq!(
+            ranked,
+            vec!["spelon", "figy", "cheri", "pecha", "mago", "chesto", "wiki"]
+        );
+
+        let hardy = nature("hardy", "spicy", "spicy");
+        assert!(rank_for_nature(&berries, &hardy)
+            .iter()
+            .all(|r| r.score == 0));
+    }
+
+    #[test]
+    fn reports_confusing_berries() {
+        let berries = berries();
+
+        let adamant = nature("adamant", "spicy", "dry");
+        let confusing: Vec<&str> = confusing_berries(&berries, &adamant)
+            .iter()
+            .map(|b| b.name.as_str())
+            .collect();
+        assert_eq!(confusing, vec!["wiki"]);
+
+        let hardy = nature("hardy", "spicy", "spicy");
+        assert!(confusing_berries(&berries, &hardy).is_empty());
+    }
+}
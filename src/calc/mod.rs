@@ -0,0 +1,9 @@
+//! Game mechanics calculators built on top of the API models.
+
+pub mod breeding;
+pub mod cooking;
+pub mod damage;
+pub mod farming;
+pub mod flavor;
+pub mod stats;
+pub mod types;
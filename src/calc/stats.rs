@@ -0,0 +1,479 @@
+//! Actual stats of a Pokémon from its base stats, nature, IVs, EVs and level.
+
+use serde::Serialize;
+
+use crate::models::v2::pokemon::{Nature, Pokemon};
+use crate::models::v2::resource::NamedResource;
+use crate::{Error, Result};
+
+/// Highest individual value of a stat since generation III.
+pub const MAX_IV: u32 = 31;
+
+/// Highest effort value of a single stat since generation III.
+pub const MAX_EV: u32 = 252;
+
+/// Highest sum of effort values of all stats since generation III.
+pub const MAX_TOTAL_EVS: u32 = 510;
+
+/// Highest determinant value of a stat in generations I and II.
+pub const MAX_DV: u32 = 15;
+
+/// Highest stat experience of a stat in generations I and II.
+pub const MAX_STAT_EXP: u32 = 65535;
+
+/// Permanent stats of a Pokémon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stat {
+    Hp,
+    Attack,
+    Defense,
+    SpecialAttack,
+    SpecialDefense,
+    Speed,
+}
+
+impl Stat {
+    /// Every stat in the order the API lists them.
+    pub const ALL: [Stat; 6] = [
+        Stat::Hp,
+        Stat::Attack,
+        Stat::Defense,
+        Stat::SpecialAttack,
+        Stat::SpecialDefense,
+        Stat::Speed,
+    ];
+
+    /// Name of the `stat` endpoint resource.
+    pub fn name(self) -> &'static str {
+        match self {
+            Stat::Hp => "hp",
+            Stat::Attack => "attack",
+            Stat::Defense => "defense",
+            Stat::SpecialAttack => "special-attack",
+            Stat::SpecialDefense => "special-defense",
+            Stat::Speed => "speed",
+        }
+    }
+
+    /// Finds the stat by the name of the `stat` endpoint resource.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|stat| stat.name() == name)
+    }
+
+    fn from_resource(resource: &NamedResource) -> Result<Self> {
+        Self::from_name(&resource.name)
+            .ok_or_else(|| Error::InvalidArgument(format!("unknown stat `{}`", resource.name)))
+    }
+}
+
+/// Value of every permanent stat, used for base stats, IVs, EVs and the computed stats alike.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Stats {
+    pub hp: u32,
+    pub attack: u32,
+    pub defense: u32,
+    pub special_attack: u32,
+    pub special_defense: u32,
+    pub speed: u32,
+}
+
+impl Stats {
+    /// Creates stats in the order the API lists them.
+    pub fn new(
+        hp: u32,
+        attack: u32,
+        defense: u32,
+        special_attack: u32,
+        special_defense: u32,
+        speed: u32,
+    ) -> Self {
+        Self {
+            hp,
+            attack,
+            defense,
+            special_attack,
+            special_defense,
+            speed,
+        }
+    }
+
+    /// Creates stats with the same value for every stat, e.g. `Stats::splat(MAX_IV)`.
+    pub fn splat(value: u32) -> Self {
+        Self::new(value, value, value, value, value, value)
+    }
+
+    /// Base stats of the Pokémon.
+    pub fn base_of(pokemon: &Pokemon) -> Result<Self> {
+        let mut stats = Self::default();
+        let mut found = Vec::with_capacity(Stat::ALL.len());
+
+        for entry in pokemon.stats.iter() {
+            let stat = Stat::from_resource(&entry.stat)?;
+            stats.set(stat, entry.base_stat as u32);
+            found.push(stat);
+        }
+
+        match Stat::ALL.iter().find(|stat| !found.contains(stat)) {
+            Some(missing) => Err(Error::InvalidArgument(format!(
+                "pokemon `{}` has no base {} stat",
+                pokemon.name,
+                missing.name()
+            ))),
+            None => Ok(stats),
+        }
+    }
+
+    /// Value of the stat.
+    pub fn get(&self, stat: Stat) -> u32 {
+        match stat {
+            Stat::Hp => self.hp,
+            Stat::Attack => self.attack,
+            Stat::Defense => self.defense,
+            Stat::SpecialAttack => self.special_attack,
+            Stat::SpecialDefense => self.special_defense,
+            Stat::Speed => self.speed,
+        }
+    }
+
+    /// Changes value of the stat.
+    pub fn set(&mut self, stat: Stat, value: u32) {
+        match stat {
+            Stat::Hp => self.hp = value,
+            Stat::Attack => self.attack = value,
+            Stat::Defense => self.defense = value,
+            Stat::SpecialAttack => self.special_attack = value,
+            Stat::SpecialDefense => self.special_defense = value,
+            Stat::Speed => self.speed = value,
+        }
+    }
+
+    /// Sum of all stats.
+    pub fn total(&self) -> u32 {
+        Stat::ALL.iter().map(|stat| self.get(*stat)).sum()
+    }
+}
+
+/// Stats raised and lowered by 10% by a nature.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NatureModifier {
+    /// The stat increased by 10%.
+    pub increased: Option<Stat>,
+
+    /// The stat decreased by 10%.
+    pub decreased: Option<Stat>,
+}
+
+impl NatureModifier {
+    /// Modifier of a neutral nature, such as Hardy.
+    pub fn neutral() -> Self {
+        Self::default()
+    }
+
+    /// Modifier of the nature. Neutral natures have neither stat set.
+    pub fn from_nature(nature: &Nature) -> Result<Self> {
+        Ok(Self {
+            increased: nature
+                .increased_stat
+                .as_ref()
+                .map(Stat::from_resource)
+                .transpose()?,
+            decreased: nature
+                .decreased_stat
+                .as_ref()
+                .map(Stat::from_resource)
+                .transpose()?,
+        })
+    }
+
+    /// Multiplier of the stat in percent.
+    pub fn percent(&self, stat: Stat) -> u32 {
+        if self.increased == self.decreased {
+            100
+        } else if self.increased == Some(stat) {
+            110
+        } else if self.decreased == Some(stat) {
+            90
+        } else {
+            100
+        }
+    }
+}
+
+/// Computes stats using the formulas of generation III and later.
+///
+/// IVs must be in `0..=31`, EVs in `0..=252` with at most 510 in total
+/// and the level in `1..=100`. A Pokémon with the base HP of `1`, i.e. Shedinja,
+/// always has one hit point.
+pub fn calculate(
+    base: &Stats,
+    ivs: &Stats,
+    evs: &Stats,
+    level: u32,
+    nature: &NatureModifier,
+) -> Result<Stats> {
+    check_level(level)?;
+    check_each("IV", ivs, MAX_IV)?;
+    check_each("EV", evs, MAX_EV)?;
+    if evs.total() > MAX_TOTAL_EVS {
+        return Err(Error::InvalidArgument(format!(
+            "EVs total {} is over {}",
+            evs.total(),
+            MAX_TOTAL_EVS
+        )));
+    }
+
+    let mut stats = Stats::default();
+    for stat in Stat::ALL.iter().copied() {
+        let raw = (2 * base.get(stat) + ivs.get(stat) + evs.get(stat) / 4) * level / 100;
+        let value = match stat {
+            Stat::Hp if base.hp == 1 => 1,
+            Stat::Hp => raw + level + 10,
+            _ => (raw + 5) * nature.percent(stat) / 100,
+        };
+        stats.set(stat, value);
+    }
+
+    Ok(stats)
+}
+
+/// Determinant values of generations I and II.
+/// The HP DV is not stored but derived from the lowest bits of the others.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Dvs {
+    pub attack: u32,
+    pub defense: u32,
+    pub speed: u32,
+    pub special: u32,
+}
+
+impl Dvs {
+    /// Creates determinant values.
+    pub fn new(attack: u32, defense: u32, speed: u32, special: u32) -> Self {
+        Self {
+            attack,
+            defense,
+            speed,
+            special,
+        }
+    }
+
+    /// The HP determinant value.
+    pub fn hp(&self) -> u32 {
+        (self.attack & 1) << 3
+            | (self.defense & 1) << 2
+            | (self.speed & 1) << 1
+            | (self.special & 1)
+    }
+
+    fn get(&self, stat: Stat) -> u32 {
+        match stat {
+            Stat::Hp => self.hp(),
+            Stat::Attack => self.attack,
+            Stat::Defense => self.defense,
+            Stat::SpecialAttack | Stat::SpecialDefense => self.special,
+            Stat::Speed => self.speed,
+        }
+    }
+}
+
+/// Computes stats using the formulas of generations I and II.
+///
+/// DVs must be in `0..=15`, stat experience in `0..=65535` and the level in `1..=100`.
+/// Special attack and special defense share the special DV and stat experience,
+/// the latter is taken from `stat_exp.special_attack`. Base stats are used as given,
+/// so a generation I special stat is computed by passing it as both special stats.
+pub fn calculate_gen12(base: &Stats, dvs: &Dvs, stat_exp: &Stats, level: u32) -> Result<Stats> {
+    check_level(level)?;
+    for (stat, dv) in [
+        ("attack", dvs.attack),
+        ("defense", dvs.defense),
+        ("speed", dvs.speed),
+        ("special", dvs.special),
+    ]
+    .iter()
+    {
+        if *dv > MAX_DV {
+            return Err(Error::InvalidArgument(format!(
+                "{} DV {} is over {}",
+                stat, dv, MAX_DV
+            )));
+        }
+    }
+    check_each("stat experience", stat_exp, MAX_STAT_EXP)?;
+
+    let mut stats = Stats::default();
+    for stat in Stat::ALL.iter().copied() {
+        let exp = match stat {
+            Stat::SpecialDefense => stat_exp.special_attack,
+            stat => stat_exp.get(stat),
+        };
+        // The games cap the rounded up root at 255, so maximum stat experience gives 63.
+        let exp_bonus = (f64::from(exp).sqrt().ceil() as u32).min(255) / 4;
+        let raw = ((base.get(stat) + dvs.get(stat)) * 2 + exp_bonus) * level / 100;
+        stats.set(
+            stat,
+            match stat {
+                Stat::Hp => raw + level + 10,
+                _ => raw + 5,
+            },
+        );
+    }
+
+    Ok(stats)
+}
+
+fn check_level(level: u32) -> Result<()> {
+    if (1..=100).contains(&level) {
+        Ok(())
+    } else {
+        Err(Error::InvalidArgument(format!(
+            "level {} is out of 1..=100",
+            level
+        )))
+    }
+}
+
+fn check_each(kind: &str, values: &Stats, max: u32) -> Result<()> {
+    match Stat::ALL.iter().find(|stat| values.get(**stat) > max) {
+        Some(stat) => Err(Error::InvalidArgument(format!(
+            "{} {} of {} is over {}",
+            kind,
+            values.get(*stat),
+            stat.name(),
+            max
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{calculate, calculate_gen12, Dvs, NatureModifier, Stat, Stats, MAX_IV};
+    use crate::models::v2::pokemon::{Nature, Pokemon};
+    use serde_json::json;
+
+    fn garchomp() -> Pokemon {
+        let stats: Vec<_> = [108, 130, 95, 80, 85, 102]
+            .iter()
+            .zip(Stat::ALL.iter())
+            .map(|(base, stat)| {
+                json!({
+                    "base_stat": base,
+                    "effort": 0,
+                    "stat": { "name": stat.name(), "url": "" }
+                })
+            })
+            .collect();
+
+        serde_json::from_value(json!({
+            "id": 445,
+            "name": "garchomp",
+            "base_experience": 300,
+            "height": 19,
+            "is_default": true,
+            "order": 561,
+            "weight": 950,
+            "abilities": [],
+            "forms": [],
+            "game_indices": [],
+            "held_items": [],
+            "location_area_encounters": "",
+            "moves": [],
+            "sprites": {},
+            "species": { "name": "garchomp", "url": "" },
+            "stats": stats,
+            "types": []
+        }))
+        .unwrap()
+    }
+
+    fn adamant() -> Nature {
+        serde_json::from_value(json!({
+            "id": 3,
+            "name": "adamant",
+            "decreased_stat": { "name": "special-attack", "url": "" },
+            "increased_stat": { "name": "attack", "url": "" },
+            "hates_flavor": { "name": "dry", "url": "" },
+            "likes_flavor": { "name": "spicy", "url": "" },
+            "pokeathlon_stat_changes": [],
+            "move_battle_style_preferences": [],
+            "names": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn computes_modern_stats() {
+        let base = Stats::base_of(&garchomp()).unwrap();
+        let nature = NatureModifier::from_nature(&adamant()).unwrap();
+        assert_eq!(nature.percent(Stat::Attack), 110);
+        assert_eq!(nature.percent(Stat::SpecialAttack), 90);
+
+        let stats = calculate(
+            &base,
+            &Stats::new(24, 12, 30, 16, 23, 5),
+            &Stats::new(74, 190, 91, 48, 84, 23),
+            78,
+            &nature,
+        )
+        .unwrap();
+
+        assert_eq!(stats, Stats::new(289, 278, 193, 135, 171, 171));
+    }
+
+    #[test]
+    fn validates_bounds() {
+        let base = Stats::base_of(&garchomp()).unwrap();
+        let neutral = NatureModifier::neutral();
+
+        assert!(calculate(&base, &Stats::splat(32), &Stats::default(), 50, &neutral).is_err());
+        assert!(calculate(&base, &Stats::default(), &Stats::splat(253), 50, &neutral).is_err());
+        assert!(calculate(
+            &base,
+            &Stats::default(),
+            &Stats::new(252, 252, 8, 0, 0, 0),
+            50,
+            &neutral
+        )
+        .is_err());
+        assert!(calculate(&base, &Stats::splat(MAX_IV), &Stats::default(), 0, &neutral).is_err());
+        assert!(calculate(&base, &Stats::default(), &Stats::default(), 101, &neutral).is_err());
+        assert!(calculate_gen12(&base, &Dvs::new(16, 0, 0, 0), &Stats::default(), 50).is_err());
+        assert!(calculate_gen12(&base, &Dvs::default(), &Stats::splat(65536), 50).is_err());
+    }
+
+    #[test]
+    fn shedinja_has_one_hp() {
+        let base = Stats::new(1, 90, 45, 30, 30, 40);
+        let stats = calculate(
+            &base,
+            &Stats::splat(MAX_IV),
+            &Stats::new(252, 0, 0, 0, 0, 0),
+            100,
+            &NatureModifier::neutral(),
+        )
+        .unwrap();
+
+        assert_eq!(stats.hp, 1);
+    }
+
+    #[test]
+    fn computes_gen12_stats() {
+        let dvs = Dvs::new(15, 15, 15, 15);
+        assert_eq!(dvs.hp(), 15);
+        assert_eq!(Dvs::new(14, 15, 14, 15).hp(), 5);
+
+        // Mew at level 100 with maximum DVs and stat experience.
+        let stats = calculate_gen12(&Stats::splat(100), &dvs, &Stats::splat(65535), 100).unwrap();
+        assert_eq!(stats, Stats::new(403, 298, 298, 298, 298, 298));
+
+        // Snorlax at level 100 with maximum DVs and stat experience in Red and Blue.
+        let snorlax = Stats::new(160, 110, 65, 65, 65, 30);
+        let stats = calculate_gen12(&snorlax, &dvs, &Stats::splat(65535), 100).unwrap();
+        assert_eq!(stats.hp, 523);
+
+        let stats =
+            calculate_gen12(&Stats::splat(100), &Dvs::default(), &Stats::default(), 50).unwrap();
+        assert_eq!(stats, Stats::new(160, 105, 105, 105, 105, 105));
+    }
+}
@@ -0,0 +1,391 @@
+//! Type effectiveness chart.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::api::v2::client::ApiClient;
+use crate::api::v2::endpoint::fetch_all;
+use crate::mirror::load_all;
+use crate::models::v2::pokemon::{Type, TypeRelations};
+use crate::models::v2::resource::NamedResource;
+use crate::{Error, Result};
+
+/// Damage multipliers of attacking types against defending types.
+///
+/// The chart is built from `damage_relations` of the `type` endpoint resources.
+/// Both the `*_damage_to` relations of the attacking type and the `*_damage_from` relations
+/// of the defending type give the multiplier, building fails when they disagree.
+/// Charts of previous generations use `past_damage_relations` and leave out
+/// the types introduced later, e.g. there is no Fairy type before generation VI.
+#[derive(Clone, Debug, Default)]
+pub struct TypeChart {
+    /// Names of the types in the chart.
+    types: Vec<String>,
+
+    /// Multipliers different from `1` keyed by attacking and defending type names.
+    multipliers: HashMap<(String, String), f64>,
+}
+
+impl TypeChart {
+    /// Builds the chart of the current generation.
+    pub fn new(types: &[Type]) -> Result<Self> {
+        Self::build(types, None)
+    }
+
+    /// Builds the chart as it was in the given generation, e.g. `5` for generation V.
+    pub fn for_generation(types: &[Type], generation: u32) -> Result<Self> {
+        Self::build(types, Some(generation))
+    }
+
+    /// Builds the current chart from the types fetched with the client.
+    /// Responses go through the client cache, if it has one.
+    pub async fn from_api(client: &ApiClient) -> Result<Self> {
+        Self::new(&fetch_types(client).await?)
+    }
+
+    /// Builds the current chart from the types stored in the directory by `Mirror`,
+    /// so it works without network.
+    pub fn from_snapshot<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        Self::new(&load_types(dir)?)
+    }
+
+    fn build(types: &[Type], generation: Option<u32>) -> Result<Self> {
+        let mut chart = TypeChart::default();
+
+        let types: Vec<&Type> = types
+            .iter()
+            .filter(|t| match (generation, generation_number(&t.generation)) {
+                (Some(generation), Some(introduced)) => introduced <= generation,
+                _ => true,
+            })
+            .collect();
+
+        for t in types.iter() {
+            if !chart.types.contains(&t.name) {
+                chart.types.push(t.name.clone());
+            }
+        }
+
+        for t in types.iter() {
+            let relations = relations_in(t, generation);
+            let known = |other: &NamedResource| chart.types.contains(&other.name);
+            let mut found = Vec::new();
+
+            for (targets, multiplier) in [
+                (&relations.no_damage_to, 0.0),
+                (&relations.half_damage_to, 0.5),
+                (&relations.double_damage_to, 2.0),
+            ]
+            .iter()
+            {
+                for target in targets.iter().filter(|r| known(r)) {
+                    found.push(((t.name.clone(), target.name.clone()), *multiplier));
+                }
+            }
+
+            for (sources, multiplier) in [
+                (&relations.no_damage_from, 0.0),
+                (&relations.half_damage_from, 0.5),
+                (&relations.double_damage_from, 2.0),
+            ]
+            .iter()
+            {
+                for source in sources.iter().filter(|r| known(r)) {
+                    found.push(((source.name.clone(), t.name.clone()), *multiplier));
+                }
+            }
+
+            for (key, multiplier) in found {
+                match chart.multipliers.entry(key) {
+                    Entry::Occupied(entry) if *entry.get() != multiplier => {
+                        let (attacking, defending) = entry.key();
+                        return Err(Error::InvalidArgument(format!(
+                            "conflicting damage relations of `{}` against `{}`: {} and {}",
+                            attacking,
+                            defending,
+                            entry.get(),
+                            multiplier
+                        )));
+                    }
+                    Entry::Occupied(_) => {}
+                    Entry::Vacant(entry) => {
+                        entry.insert(multiplier);
+                    }
+                }
+            }
+        }
+
+        Ok(chart)
+    }
+
+    /// Names of the types in the chart.
+    pub fn types(&self) -> &[String] {
+        &self.types
+    }
+
+    /// Damage multiplier of the attacking type against a single defending type.
+    pub fn effectiveness(&self, attacking: &str, defending: &str) -> Result<f64> {
+        self.check(attacking)?;
+        self.check(defending)?;
+
+        Ok(*self
+            .multipliers
+            .get(&(attacking.to_string(), defending.to_string()))
+            .unwrap_or(&1.0))
+    }
+
+    /// Damage multiplier of the attacking type against a Pokémon with one or two types.
+    pub fn multiplier(&self, attacking: &str, defending: &[&str]) -> Result<f64> {
+        if defending.is_empty() || defending.len() > 2 {
+            return Err(Error::InvalidArgument(format!(
+                "expected one or two defending types, got {}",
+                defending.len()
+            )));
+        }
+
+        defending.iter().try_fold(1.0, |multiplier, defending| {
+            Ok(multiplier * self.effectiveness(attacking, defending)?)
+        })
+    }
+
+    fn check(&self, name: &str) -> Result<()> {
+        if self.types.iter().any(|t| t == name) {
+            Ok(())
+        } else {
+            Err(Error::InvalidArgument(format!("unknown type `{}`", name)))
+        }
+    }
+}
+
+/// Fetches every resource of the `type` endpoint.
+pub async fn fetch_types(client: &ApiClient) -> Result<Vec<Type>> {
+    fetch_all(&client.types()).await
+}
+
+/// Loads every type stored in the directory using the `api-data` layout written by `Mirror`.
+pub fn load_types<P: AsRef<Path>>(dir: P) -> Result<Vec<Type>> {
+    load_all(dir.as_ref(), "type")
+}
+
+/// Damage relations of the type in the generation.
+/// Past relations apply up to and including their generation, so the earliest one
+/// not older than the requested generation wins.
+fn relations_in(t: &Type, generation: Option<u32>) -> &TypeRelations {
+    let generation = match generation {
+        Some(generation) => generation,
+        None => return &t.damage_relations,
+    };
+
+    t.past_damage_relations
+        .iter()
+        .filter_map(|past| generation_number(&past.generation).map(|number| (number, past)))
+        .filter(|(number, _)| *number >= generation)
+        .min_by_key(|(number, _)| *number)
+        .map(|(_, past)| &past.damage_relations)
+        .unwrap_or(&t.damage_relations)
+}
+
+/// Number of the generation, generation ids follow their numbers.
+fn generation_number(generation: &NamedResource) -> Option<u32> {
+    generation.id().map(|id| id as u32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::TypeChart;
+    use crate::models::v2::pokemon::{Type, TypeRelations, TypeRelationsPast};
+    use crate::models::v2::resource::NamedResource;
+
+    fn reference(endpoint: &str, name: &str, id: usize) -> NamedResource {
+        NamedResource {
+            name: name.into(),
+            url: format!("https://pokeapi.co/api/v2/{}/{}/", endpoint, id),
+        }
+    }
+
+    fn types(names: &[&str]) -> Vec<NamedResource> {
+        names
+            .iter()
+            .map(|name| reference("type", name, 0))
+            .collect()
+    }
+
+    fn new_type(name: &str, generation: usize, relations: TypeRelations) -> Type {
+        Type {
+            id: 0,
+            name: name.into(),
+            damage_relations: relations,
+            past_damage_relations: vec![],
+            game_indices: vec![],
+            generation: reference("generation", "", generation),
+            move_damage_class: None,
+            names: vec![],
+            pokemon: vec![],
+            moves: vec![],
+        }
+    }
+
+    fn chart_types() -> Vec<Type> {
+        let mut steel = new_type(
+            "steel",
+            2,
+            TypeRelations {
+                half_damage_to: types(&["fire", "water", "steel"]),
+                double_damage_to: types(&["fairy"]),
+                half_damage_from: types(&["normal", "grass", "steel", "fairy"]),
+                double_damage_from: types(&["fire"]),
+                ..TypeRelations::default()
+            },
+        );
+        steel.past_damage_relations.push(TypeRelationsPast {
+            generation: reference("generation", "generation-v", 5),
+            damage_relations: TypeRelations {
+                half_damage_to: types(&["fire", "water", "steel"]),
+                half_damage_from: types(&["normal", "grass", "steel", "ghost"]),
+                double_damage_from: types(&["fire"]),
+                ..TypeRelations::default()
+            },
+        });
+
+        vec![
+            new_type(
+                "normal",
+                1,
+                TypeRelations {
+                    half_damage_to: types(&["steel"]),
+                    no_damage_to: types(&["ghost"]),
+                    no_damage_from: types(&["ghost"]),
+                    ..TypeRelations::default()
+                },
+            ),
+            new_type(
+                "fire",
+                1,
+                TypeRelations {
+                    half_damage_to: types(&["fire", "water"]),
+                    double_damage_to: types(&["grass", "steel"]),
+                    ..TypeRelations::default()
+                },
+            ),
+            new_type(
+                "water",
+                1,
+                TypeRelations {
+                    half_damage_to: types(&["water", "grass"]),
+                    double_damage_to: types(&["fire"]),
+                    ..TypeRelations::default()
+                },
+            ),
+            new_type(
+                "grass",
+                1,
+                TypeRelations {
+                    half_damage_to: types(&["fire", "grass", "steel"]),
+                    double_damage_to: types(&["water"]),
+                    ..TypeRelations::default()
+                },
+            ),
+            new_type(
+                "ghost",
+                1,
+                TypeRelations {
+                    no_damage_to: types(&["normal"]),
+                    ..TypeRelations::default()
+                },
+            ),
+            steel,
+            new_type(
+                "fairy",
+                6,
+                TypeRelations {
+                    half_damage_to: types(&["fire", "steel"]),
+                    ..TypeRelations::default()
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn single_type_effectiveness() {
+        let chart = TypeChart::new(&chart_types()).unwrap();
+
+        assert_eq!(chart.effectiveness("fire", "grass").unwrap(), 2.0);
+        assert_eq!(chart.effectiveness("water", "grass").unwrap(), 0.5);
+        assert_eq!(chart.effectiveness("normal", "ghost").unwrap(), 0.0);
+        assert_eq!(chart.effectiveness("ghost", "normal").unwrap(), 0.0);
+        assert_eq!(chart.effectiveness("water", "normal").unwrap(), 1.0);
+        assert_eq!(chart.effectiveness("fairy", "steel").unwrap(), 0.5);
+        assert!(chart.effectiveness("dragon", "normal").is_err());
+    }
+
+    #[test]
+    fn dual_type_multiplier() {
+        let chart = TypeChart::new(&chart_types()).unwrap();
+
+        assert_eq!(chart.multiplier("fire", &["grass", "steel"]).unwrap(), 4.0);
+        assert_eq!(chart.multiplier("grass", &["fire", "steel"]).unwrap(), 0.25);
+        assert_eq!(
+            chart.multiplier("normal", &["ghost", "steel"]).unwrap(),
+            0.0
+        );
+        assert_eq!(chart.multiplier("water", &["fire", "grass"]).unwrap(), 1.0);
+        assert!(chart.multiplier("fire", &[]).is_err());
+        assert!(chart
+            .multiplier("fire", &["grass", "steel", "water"])
+            .is_err());
+    }
+
+    #[test]
+    fn generation_specific_chart() {
+        let chart = TypeChart::for_generation(&chart_types(), 5).unwrap();
+
+        assert!(!chart.types().contains(&"fairy".to_string()));
+        assert!(chart.effectiveness("fairy", "steel").is_err());
+        assert_eq!(chart.effectiveness("ghost", "steel").unwrap(), 0.5);
+
+        let chart = TypeChart::for_generation(&chart_types(), 6).unwrap();
+        assert_eq!(chart.effectiveness("ghost", "steel").unwrap(), 1.0);
+        assert_eq!(chart.effectiveness("steel", "fairy").unwrap(), 2.0);
+    }
+
+    #[test]
+    fn chart_does_not_depend_on_order() {
+        let types = chart_types();
+        let chart = TypeChart::new(&types).unwrap();
+
+        let mut shuffled = types.clone();
+        shuffled.reverse();
+        shuffled.rotate_left(3);
+        shuffled.push(types[1].clone());
+        let other = TypeChart::new(&shuffled).unwrap();
+
+        assert_eq!(other.types().len(), chart.types().len());
+        for attacking in chart.types() {
+            for defending in chart.types() {
+                assert_eq!(
+                    other.effectiveness(attacking, defending).unwrap(),
+                    chart.effectiveness(attacking, defending).unwrap(),
+                    "{} against {}",
+                    attacking,
+                    defending
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_conflicting_relations() {
+        let mut chart_types = chart_types();
+        chart_types.push(new_type(
+            "water",
+            1,
+            TypeRelations {
+                double_damage_from: types(&["fire"]),
+                ..TypeRelations::default()
+            },
+        ));
+
+        assert!(TypeChart::new(&chart_types).is_err());
+    }
+}
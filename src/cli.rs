@@ -0,0 +1,503 @@
+//! Command line interface for querying the API, used by the `pokeapi` binary.
+//!
+//! Examples:
+//! ```text
+//! pokeapi get berry cheri
+//! pokeapi list berry --offset 10 --limit 5
+//! pokeapi --format yaml follow berry/1 firmness
+//! pokeapi drift pokemon --limit 50
+//! ```
+
+use std::env;
+use std::path::PathBuf;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::v2::cache::FileCache;
+use crate::api::v2::client::ApiClient;
+use crate::api::v2::endpoint::ApiEndpoint;
+use crate::api::v2::key::ResourceKey;
+use crate::api::v2::resource::ApiNamedResourceList;
+use crate::api::v2::schema::DecodeMode;
+use crate::{Error, Result};
+
+/// Endpoints available from the command line.
+pub const ENDPOINTS: &[&str] = &[
+    "berry",
+    "berry-firmness",
+    "type",
+    "pokemon",
+    "nature",
+    "pokemon-species",
+    "egg-group",
+    "move",
+];
+
+/// Output formats.
+const FORMATS: &[&str] = &["json", "yaml", "table"];
+
+/// Calls the generic function with the endpoint object matching the name.
+macro_rules! dispatch {
+    ($client:expr, $endpoint:expr, $func:ident($($arg:expr),*)) => {
+        match $endpoint {
+            "berry" => $func(&$client.berries(), $($arg),*).await,
+            "berry-firmness" => $func(&$client.berry_firmnesses(), $($arg),*).await,
+            "type" => $func(&$client.types(), $($arg),*).await,
+            "pokemon" => $func(&$client.pokemon(), $($arg),*).await,
+            "nature" => $func(&$client.natures(), $($arg),*).await,
+            "pokemon-species" => $func(&$client.pokemon_species(), $($arg),*).await,
+            "egg-group" => $func(&$client.egg_groups(), $($arg),*).await,
+            "move" => $func(&$client.moves(), $($arg),*).await,
+            name => Err(Error::InvalidArgument(format!("unknown endpoint `{}`", name))),
+        }
+    };
+}
+
+/// Format of the command output.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Json,
+    Yaml,
+    Table,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Result<Format> {
+        match name {
+            "json" => Ok(Format::Json),
+            "yaml" => Ok(Format::Yaml),
+            "table" => Ok(Format::Table),
+            _ => Err(Error::InvalidArgument(format!("unknown format `{}`", name))),
+        }
+    }
+}
+
+/// Command line definition.
+pub fn app() -> App<'static, 'static> {
+    let endpoint = Arg::with_name("endpoint")
+        .help("Endpoint name")
+        .required(true)
+        .possible_values(ENDPOINTS);
+
+    App::new("pokeapi")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Queries PokeAPI v2")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .short("f")
+                .takes_value(true)
+                .possible_values(FORMATS)
+                .default_value("json")
+                .help("Output format"),
+        )
+        .arg(
+            Arg::with_name("no-cache")
+                .long("no-cache")
+                .help("Do not read or write cached responses"),
+        )
+        .arg(
+            Arg::with_name("cache-dir")
+                .long("cache-dir")
+                .takes_value(true)
+                .help("Directory of cached responses"),
+        )
+        .subcommand(
+            SubCommand::with_name("get")
+                .about("Gets a resource by its id or name")
+                .arg(endpoint.clone())
+                .arg(
+                    Arg::with_name("key")
+                        .help("Resource id or name")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("list")
+                .about("Lists resources of the endpoint")
+                .arg(endpoint.clone())
+                .arg(
+                    Arg::with_name("offset")
+                        .long("offset")
+                        .takes_value(true)
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::with_name("limit")
+                        .long("limit")
+                        .takes_value(true)
+                        .default_value("20"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("follow")
+                .about("Gets the resources referenced by a field of the resource")
+                .arg(
+                    Arg::with_name("resource")
+                        .help("Resource as `{endpoint}/{key}`, e.g. `berry/1`")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("field")
+                        .help("Field path, nested fields are separated by dots, e.g. `flavors.flavor`")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("drift")
+                .about("Reports fields of the resources not covered by the models")
+                .arg(endpoint)
+                .arg(
+                    Arg::with_name("limit")
+                        .long("limit")
+                        .takes_value(true)
+                        .default_value("20")
+                        .help("Number of resources to check"),
+                ),
+        )
+}
+
+/// Runs the command given parsed arguments and returns the rendered output.
+pub async fn run(matches: &ArgMatches<'_>) -> Result<String> {
+    let format = Format::from_name(matches.value_of("format").unwrap_or("json"))?;
+
+    let mut client = ApiClient::new()?;
+    if !matches.is_present("no-cache") {
+        let dir = matches
+            .value_of("cache-dir")
+            .map(PathBuf::from)
+            .or_else(default_cache_dir);
+
+        if let Some(dir) = dir {
+            client = client.with_cache(FileCache::new(dir));
+        }
+    }
+
+    let value = match matches.subcommand() {
+        ("get", Some(args)) => {
+            let key = args.value_of("key").unwrap_or_default();
+            dispatch!(
+                client,
+                args.value_of("endpoint").unwrap_or_default(),
+                get(key)
+            )?
+        }
+        ("list", Some(args)) => {
+            let offset = parse_number(args.value_of("offset"), "offset")?;
+            let limit = parse_number(args.value_of("limit"), "limit")?;
+            dispatch!(
+                client,
+                args.value_of("endpoint").unwrap_or_default(),
+                list(offset, limit)
+            )?
+        }
+        ("follow", Some(args)) => {
+            let resource = args.value_of("resource").unwrap_or_default();
+            let field = args.value_of("field").unwrap_or_default();
+            follow(&client, resource, field).await?
+        }
+        ("drift", Some(args)) => {
+            let limit = parse_number(args.value_of("limit"), "limit")?;
+            let client = client.with_decode_mode(DecodeMode::Lenient);
+            dispatch!(
+                client,
+                args.value_of("endpoint").unwrap_or_default(),
+                drift(limit)
+            )?
+        }
+        (name, _) => {
+            return Err(Error::InvalidArgument(format!(
+                "unknown command `{}`",
+                name
+            )));
+        }
+    };
+
+    render(&value, format)
+}
+
+/// Default directory of cached responses: `$XDG_CACHE_HOME/pokeapi` or `$HOME/.cache/pokeapi`.
+fn default_cache_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .map(|dir| dir.join("pokeapi"))
+}
+
+fn parse_number(value: Option<&str>, name: &str) -> Result<usize> {
+    value
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| Error::InvalidArgument(format!("`{}` must be a number", name)))
+}
+
+async fn get<E>(endpoint: &E, key: &str) -> Result<Value>
+where
+    E: ApiEndpoint + Sync,
+    E::Model: Serialize,
+{
+    let model = endpoint.get(key.parse::<ResourceKey>()?).await?;
+
+    Ok(serde_json::to_value(model)?)
+}
+
+async fn list<E>(endpoint: &E, offset: usize, limit: usize) -> Result<Value>
+where
+    E: ApiEndpoint + Sync,
+{
+    let list = endpoint.all_paginated(offset, limit).await?;
+    let results = list
+        .resources()
+        .iter()
+        .map(|res| serde_json::json!({ "name": res.name(), "url": res.url() }))
+        .collect();
+
+    Ok(serde_json::json!({
+        "count": list.count(),
+        "results": Value::Array(results),
+    }))
+}
+
+/// Fetches the first resources of the endpoint and reports the fields not matching the models.
+async fn drift<E>(endpoint: &E, limit: usize) -> Result<Value>
+where
+    E: ApiEndpoint + Sync,
+{
+    let list = endpoint.all_paginated(0, limit).await?;
+    for resource in list.resources() {
+        resource.get().await?;
+    }
+
+    Ok(serde_json::to_value(endpoint.client().drift_report())?)
+}
+
+/// Gets the resource, then every resource referenced under the field.
+async fn follow(client: &ApiClient, resource: &str, field: &str) -> Result<Value> {
+    let mut parts = resource.trim_matches('/').splitn(2, '/');
+    let (endpoint, key) = match (parts.next(), parts.next()) {
+        (Some(endpoint), Some(key)) => (endpoint, key),
+        _ => {
+            return Err(Error::InvalidArgument(format!(
+                "resource `{}` must look like `{{endpoint}}/{{key}}`",
+                resource
+            )))
+        }
+    };
+
+    let value = dispatch!(client, endpoint, get(key))?;
+    let selected = select(&value, field);
+    if selected.is_empty() {
+        return Err(Error::InvalidArgument(format!(
+            "`{}` has no field `{}`",
+            resource, field
+        )));
+    }
+
+    let mut urls = Vec::new();
+    for value in selected {
+        collect_urls(value, &mut urls);
+    }
+
+    let mut resources = Vec::with_capacity(urls.len());
+    for url in urls {
+        resources.push(client.request_object::<Value, _>(url).await?);
+    }
+
+    Ok(match resources.len() {
+        1 => resources.remove(0),
+        _ => Value::Array(resources),
+    })
+}
+
+/// Selects values under the dotted path. Arrays on the way are traversed element-wise.
+fn select<'a>(value: &'a Value, path: &str) -> Vec<&'a Value> {
+    let mut current = vec![value];
+    for key in path.split('.').filter(|key| !key.is_empty()) {
+        current = current
+            .into_iter()
+            .flat_map(|value| match value {
+                Value::Array(values) => values.iter().filter_map(|v| v.get(key)).collect(),
+                value => value.get(key).into_iter().collect::<Vec<_>>(),
+            })
+            .collect();
+    }
+
+    current
+}
+
+/// Collects every `url` field of the resource references found in the value.
+fn collect_urls(value: &Value, urls: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => match map.get("url") {
+            Some(Value::String(url)) => urls.push(url.clone()),
+            _ => map.values().for_each(|value| collect_urls(value, urls)),
+        },
+        Value::Array(values) => values.iter().for_each(|value| collect_urls(value, urls)),
+        _ => {}
+    }
+}
+
+/// Renders the value in the given format.
+pub fn render(value: &Value, format: Format) -> Result<String> {
+    match format {
+        Format::Json => Ok(serde_json::to_string_pretty(value)?),
+        Format::Yaml => Ok(serde_yaml::to_string(value)?),
+        Format::Table => Ok(render_table(value)),
+    }
+}
+
+/// Renders resource lists as a table with a column per field,
+/// other values as a two-column table of flattened fields.
+fn render_table(value: &Value) -> String {
+    let rows: Vec<Vec<String>> = match value.get("results") {
+        Some(Value::Array(results)) => {
+            let columns: Vec<String> = match results.first() {
+                Some(Value::Object(first)) => first.keys().cloned().collect(),
+                _ => vec![],
+            };
+
+            let mut rows = vec![columns.clone()];
+            for result in results {
+                rows.push(columns.iter().map(|col| scalar(&result[col])).collect());
+            }
+            rows
+        }
+        _ => {
+            let mut rows = vec![vec!["field".to_string(), "value".to_string()]];
+            flatten(value, String::new(), &mut rows);
+            rows
+        }
+    };
+
+    let widths: Vec<usize> = (0..rows[0].len())
+        .map(|col| {
+            rows.iter()
+                .map(|row| row[col].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut out = String::new();
+    for (idx, row) in rows.iter().enumerate() {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        out.push_str(line.join(" | ").trim_end());
+        out.push('\n');
+
+        if idx == 0 {
+            let line: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+            out.push_str(&line.join("-+-"));
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+/// Flattens the value into `path: value` rows. Resource references are shown by name.
+fn flatten(value: &Value, path: String, rows: &mut Vec<Vec<String>>) {
+    match value {
+        Value::Object(map) if is_reference(map) => rows.push(vec![path, scalar(&map["name"])]),
+        Value::Object(map) => {
+            for (key, value) in map {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                flatten(value, path, rows);
+            }
+        }
+        Value::Array(values) => {
+            for (idx, value) in values.iter().enumerate() {
+                flatten(value, format!("{}[{}]", path, idx), rows);
+            }
+        }
+        value => rows.push(vec![path, scalar(value)]),
+    }
+}
+
+fn is_reference(map: &serde_json::Map<String, Value>) -> bool {
+    map.len() == 2 && map.contains_key("name") && map.contains_key("url")
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{collect_urls, render, select, Format};
+    use serde_json::json;
+
+    #[test]
+    fn selects_nested_fields() {
+        let berry = json!({
+            "firmness": { "name": "soft", "url": "https://pokeapi.co/api/v2/berry-firmness/2/" },
+            "flavors": [
+                { "potency": 10, "flavor": { "name": "spicy", "url": "https://pokeapi.co/api/v2/berry-flavor/1/" } },
+                { "potency": 0, "flavor": { "name": "dry", "url": "https://pokeapi.co/api/v2/berry-flavor/2/" } }
+            ]
+        });
+
+        let mut urls = Vec::new();
+        for value in select(&berry, "flavors.flavor") {
+            collect_urls(value, &mut urls);
+        }
+        assert_eq!(
+            urls,
+            vec![
+                "https://pokeapi.co/api/v2/berry-flavor/1/",
+                "https://pokeapi.co/api/v2/berry-flavor/2/"
+            ]
+        );
+
+        let mut urls = Vec::new();
+        for value in select(&berry, "firmness") {
+            collect_urls(value, &mut urls);
+        }
+        assert_eq!(urls, vec!["https://pokeapi.co/api/v2/berry-firmness/2/"]);
+
+        assert!(select(&berry, "unknown").is_empty());
+    }
+
+    #[test]
+    fn renders_tables() {
+        let list = json!({
+            "count": 64,
+            "results": [
+                { "name": "cheri", "url": "https://pokeapi.co/api/v2/berry/1/" },
+                { "name": "chesto", "url": "https://pokeapi.co/api/v2/berry/2/" }
+            ]
+        });
+        assert_eq!(
+            render(&list, Format::Table).unwrap(),
+            "name   | url\n\
+             -------+-----------------------------------\n\
+             cheri  | https://pokeapi.co/api/v2/berry/1/\n\
+             chesto | https://pokeapi.co/api/v2/berry/2/\n"
+        );
+
+        let firmness = json!({
+            "id": 2,
+            "berries": [{ "name": "cheri", "url": "https://pokeapi.co/api/v2/berry/1/" }]
+        });
+        assert_eq!(
+            render(&firmness, Format::Table).unwrap(),
+            "field      | value\n\
+             -----------+------\n\
+             berries[0] | cheri\n\
+             id         | 2\n"
+        );
+    }
+}
@@ -0,0 +1,416 @@
+//! Crawler following resource references of the API.
+//!
+//! Starting from a resource, the crawler follows every `NamedResource` and `Resource` url found
+//! in the documents, up to the depth limit and optionally only into the given endpoints.
+//! The result is a graph of resources and the fields referencing them, exportable as
+//! [DOT](https://graphviz.org/doc/info/lang.html) or JSON.
+//! Requests go through the client, so crawling with a cache pre-warms it for offline use.
+//!
+//! ```no_run
+//! # async fn example() -> pokemon_api::Result<()> {
+//! use pokemon_api::api::v2::client::ApiClient;
+//! use pokemon_api::crawler::Crawler;
+//!
+//! let graph = Crawler::new(ApiClient::new()?)
+//!     .with_max_depth(2)
+//!     .with_endpoints(&["berry", "berry-firmness", "berry-flavor"])
+//!     .crawl("berry/1")
+//!     .await?;
+//! std::fs::write("berry.dot", graph.to_dot())?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::v2::client::ApiClient;
+use crate::api::v2::expand::is_reference;
+use crate::models::v2::resource::{normalize_url, url_endpoint, url_path};
+use crate::Result;
+
+/// Number of references followed from the start resource by default.
+pub const DEFAULT_MAX_DEPTH: usize = 2;
+
+/// Number of requests sent at the same time.
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// Resource found by the crawler.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Node {
+    /// Url of the resource, without the trailing slash.
+    pub url: String,
+
+    /// Endpoint of the resource, e.g. `berry`.
+    pub endpoint: String,
+
+    /// Name of the resource, if known.
+    pub name: Option<String>,
+
+    /// Number of references between the start resource and this one.
+    pub depth: usize,
+
+    /// Whether the resource was fetched; resources past the depth limit are only referenced.
+    pub fetched: bool,
+
+    /// Error of the failed request for the resource, whose references are then not followed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Reference from a field of one resource to another.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Edge {
+    /// Url of the referencing resource.
+    pub from: String,
+
+    /// Url of the referenced resource.
+    pub to: String,
+
+    /// Path of the referencing field, like `flavors[].flavor`.
+    pub field: String,
+}
+
+/// Resources and references found by the crawler.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Graph {
+    /// Resources in the order they were found.
+    pub nodes: Vec<Node>,
+
+    /// References, ordered by the referencing resource url.
+    pub edges: Vec<Edge>,
+}
+
+impl Graph {
+    /// Resource with the url.
+    pub fn node(&self, url: &str) -> Option<&Node> {
+        let url = normalize_url(url);
+        self.nodes.iter().find(|node| node.url == url)
+    }
+
+    /// Serializes the graph to pretty printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Renders the graph in the DOT language of Graphviz.
+    /// Nodes are identified by their path after the API root, like `berry/1`.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph pokeapi {\n");
+        for node in self.nodes.iter() {
+            let label = match node.name {
+                Some(ref name) => format!("{} {}", node.endpoint, name),
+                None => url_path(&node.url).to_string(),
+            };
+            let style = match (node.fetched, node.error.is_some()) {
+                (_, true) => ", style=dashed, color=red",
+                (false, false) => ", style=dashed",
+                (true, false) => "",
+            };
+            dot.push_str(&format!(
+                "    {} [label={}{}];\n",
+                quote(url_path(&node.url)),
+                quote(&label),
+                style
+            ));
+        }
+        for edge in self.edges.iter() {
+            dot.push_str(&format!(
+                "    {} -> {} [label={}];\n",
+                quote(url_path(&edge.from)),
+                quote(url_path(&edge.to)),
+                quote(&edge.field)
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Follows references between API resources.
+pub struct Crawler {
+    client: ApiClient,
+    max_depth: usize,
+    endpoints: Option<BTreeSet<String>>,
+}
+
+impl Crawler {
+    /// Creates crawler requesting resources with the client.
+    pub fn new(client: ApiClient) -> Self {
+        Self {
+            client,
+            max_depth: DEFAULT_MAX_DEPTH,
+            endpoints: None,
+        }
+    }
+
+    /// Follows at most `depth` references from the start resource.
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Follows only references into the given endpoints, e.g. `berry-firmness`.
+    pub fn with_endpoints<S: AsRef<str>>(mut self, endpoints: &[S]) -> Self {
+        self.endpoints = Some(endpoints.iter().map(|e| e.as_ref().to_string()).collect());
+        self
+    }
+
+    /// Crawls from the resource given by its url or path after the API root, like `berry/1`.
+    /// Failed requests do not stop the crawl, they are recorded in the [`Node::error`] field.
+    pub async fn crawl(&self, start: &str) -> Result<Graph> {
+        let start = if start.contains("://") {
+            normalize_url(start)
+        } else {
+            format!(
+                "{}/{}",
+                self.client.api_url(),
+                normalize_url(start.trim_start_matches('/'))
+            )
+        };
+
+        let mut nodes: BTreeMap<String, usize> = BTreeMap::new();
+        let mut graph = Graph::default();
+        let mut edges = BTreeSet::new();
+        add_node(&mut graph, &mut nodes, &start, None, 0);
+
+        let mut level = vec![start];
+        for depth in 0..=self.max_depth {
+            let documents: Vec<Result<Value>> = stream::iter(level.iter())
+                .map(|url| self.client.request_object::<Value, _>(url.as_str()))
+                .buffered(MAX_CONCURRENT_REQUESTS)
+                .collect()
+                .await;
+
+            let mut next = Vec::new();
+            for (url, document) in level.iter().zip(documents) {
+                let node = &mut graph.nodes[nodes[url]];
+                let document = match document {
+                    Ok(document) => document,
+                    Err(err) => {
+                        node.error = Some(err.to_string());
+                        continue;
+                    }
+                };
+                node.fetched = true;
+                if node.name.is_none() {
+                    node.name = document["name"].as_str().map(String::from);
+                }
+
+                let mut references = Vec::new();
+                find_references(&document, String::new(), &mut references);
+                for (field, to, name) in references {
+                    if !self.follows(&to) {
+                        continue;
+                    }
+                    if !nodes.contains_key(&to) {
+                        add_node(&mut graph, &mut nodes, &to, name, depth + 1);
+                        if depth < self.max_depth {
+                            next.push(to.clone());
+                        }
+                    }
+                    edges.insert(Edge {
+                        from: url.clone(),
+                        to,
+                        field,
+                    });
+                }
+            }
+
+            if next.is_empty() {
+                break;
+            }
+            level = next;
+        }
+
+        graph.edges = edges.into_iter().collect();
+        Ok(graph)
+    }
+
+    fn follows(&self, url: &str) -> bool {
+        match self.endpoints {
+            Some(ref endpoints) => endpoints.contains(url_endpoint(url).unwrap_or_default()),
+            None => true,
+        }
+    }
+}
+
+fn add_node(
+    graph: &mut Graph,
+    nodes: &mut BTreeMap<String, usize>,
+    url: &str,
+    name: Option<String>,
+    depth: usize,
+) {
+    nodes.insert(url.to_string(), graph.nodes.len());
+    graph.nodes.push(Node {
+        url: url.to_string(),
+        endpoint: url_endpoint(url).unwrap_or_default().to_string(),
+        name,
+        depth,
+        fetched: false,
+        error: None,
+    });
+}
+
+/// Collects `(field path, url, name)` of every reference inside the document.
+fn find_references(value: &Value, path: String, found: &mut Vec<(String, String, Option<String>)>) {
+    match value {
+        Value::Object(map) if is_reference(map) && !path.is_empty() => {
+            let url = map["url"].as_str().unwrap_or_default();
+            let name = map.get("name").and_then(Value::as_str).map(String::from);
+            found.push((path, normalize_url(url), name));
+        }
+        Value::Object(map) => {
+            for (key, child) in map.iter() {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                find_references(child, path, found);
+            }
+        }
+        Value::Array(items) => {
+            for item in items.iter() {
+                find_references(item, format!("{}[]", path), found);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod test {
+    use super::Crawler;
+    use crate::models::v2::resource::url_path;
+    use crate::testing::MockServer;
+    use serde_json::json;
+
+    fn server() -> MockServer {
+        let server = MockServer::start().unwrap();
+        server.add_resource(
+            "berry",
+            json!({
+                "id": 1,
+                "name": "cheri",
+                "firmness": { "name": "soft", "url": "https://pokeapi.co/api/v2/berry-firmness/2/" },
+                "flavors": [
+                    { "potency": 10, "flavor": { "name": "spicy", "url": "https://pokeapi.co/api/v2/berry-flavor/1/" } }
+                ],
+                "item": { "name": "cheri-berry", "url": "https://pokeapi.co/api/v2/item/126/" }
+            }),
+        );
+        server.add_resource(
+            "berry-firmness",
+            json!({
+                "id": 2,
+                "name": "soft",
+                "berries": [{ "name": "cheri", "url": "https://pokeapi.co/api/v2/berry/1/" }]
+            }),
+        );
+        server.add_resource(
+            "berry-flavor",
+            json!({
+                "id": 1,
+                "name": "spicy",
+                "contest_type": { "name": "cool", "url": "https://pokeapi.co/api/v2/contest-type/1/" }
+            }),
+        );
+        server
+    }
+
+    #[tokio::test]
+    async fn crawls_references() {
+        let server = server();
+        let graph = Crawler::new(server.client().unwrap())
+            .with_max_depth(1)
+            .with_endpoints(&["berry", "berry-firmness", "berry-flavor"])
+            .crawl("berry/1/")
+            .await
+            .unwrap();
+
+        let nodes: Vec<(&str, bool)> = graph
+            .nodes
+            .iter()
+            .map(|n| (n.name.as_deref().unwrap_or_default(), n.fetched))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![("cheri", true), ("soft", true), ("spicy", true)]
+        );
+
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (url_path(&e.to), e.field.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("berry/1", "berries[]"),
+                ("berry-firmness/2", "firmness"),
+                ("berry-flavor/1", "flavors[].flavor"),
+            ]
+        );
+        assert_eq!(server.requests().len(), 3);
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"berry/1\" [label=\"berry cheri\"];"));
+        assert!(dot.contains("\"berry/1\" -> \"berry-flavor/1\" [label=\"flavors[].flavor\"];"));
+
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json().unwrap()).unwrap();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn stops_at_depth() {
+        let server = server();
+        let url = format!("{}/v2/berry/1", server.base_url());
+        let graph = Crawler::new(server.client().unwrap())
+            .with_max_depth(0)
+            .crawl(&url)
+            .await
+            .unwrap();
+
+        assert_eq!(graph.nodes.len(), 4);
+        assert!(graph.node(&url).unwrap().fetched);
+        let item = graph.nodes.iter().find(|n| n.endpoint == "item").unwrap();
+        assert!(!item.fetched);
+        assert_eq!(item.depth, 1);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn records_failed_requests() {
+        let server = server();
+        let graph = Crawler::new(server.client().unwrap())
+            .with_max_depth(1)
+            .crawl("berry/1")
+            .await
+            .unwrap();
+
+        let item = graph.nodes.iter().find(|n| n.endpoint == "item").unwrap();
+        assert!(!item.fetched);
+        assert!(item.error.is_some());
+
+        let flavor = graph
+            .nodes
+            .iter()
+            .find(|n| n.endpoint == "berry-flavor")
+            .unwrap();
+        assert!(flavor.fetched);
+        assert_eq!(flavor.error, None);
+        assert!(graph.nodes.iter().any(|n| n.endpoint == "contest-type"));
+        assert!(graph
+            .to_dot()
+            .contains("\"item/126\" [label=\"item cheri-berry\", style=dashed, color=red];"));
+    }
+}
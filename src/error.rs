@@ -7,6 +7,24 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     #[error("Request error: {0}")]
     RequestError(#[from] ReqError),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[cfg(feature = "cli")]
+    #[error("YAML error: {0}")]
+    YamlError(#[from] serde_yaml::Error),
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    SqliteError(#[from] rusqlite::Error),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("GraphQL error: {0}")]
+    GraphQlError(String),
+    #[error("Response status {0} for {1}")]
+    StatusError(u16, String),
+    #[error("Request not recorded in the cassette: {0}")]
+    UnrecordedRequest(String),
     #[error("Unknown api error")]
     Unknown,
 }
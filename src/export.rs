@@ -0,0 +1,488 @@
+//! Export of API models into flat tables: CSV, JSON Lines and columnar JSON.
+//!
+//! Models are flattened into rows of scalar columns:
+//! - nested objects become `parent.child` columns,
+//! - references to other resources (`NamedResource`) become the name of the resource,
+//! - lists of references and lists of scalars are joined with `;`,
+//! - lists of entries keyed by a reference, such as `Berry::flavors` or localized names,
+//!   become one column per referenced name, e.g. `flavors.spicy` or `names.en`,
+//! - anything else is kept as a JSON string.
+//!
+//! ```no_run
+//! # async fn example() -> pokemon_api::Result<()> {
+//! use pokemon_api::api::v2::client::ApiClient;
+//! use pokemon_api::export::{export_endpoint, ExportFormat};
+//!
+//! let client = ApiClient::new()?;
+//! let file = std::fs::File::create("berries.csv")?;
+//! export_endpoint(&client.berries(), ExportFormat::Csv, file).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::api::v2::endpoint::{ApiEndpoint, MAX_PAGINATION_LIMIT};
+use crate::api::v2::resource::{ApiNamedResource, ApiNamedResourceList};
+use crate::{Error, Result};
+
+/// Flattened model, column names mapped to scalar values.
+pub type Row = BTreeMap<String, Value>;
+
+/// Output format of the export.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row.
+    Csv,
+
+    /// One JSON object per line.
+    JsonLines,
+
+    /// A JSON object mapping every column name to the list of its values.
+    Columnar,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "jsonl" | "json-lines" => Ok(ExportFormat::JsonLines),
+            "columnar" => Ok(ExportFormat::Columnar),
+            other => Err(Error::InvalidArgument(format!(
+                "unknown export format `{}`",
+                other
+            ))),
+        }
+    }
+}
+
+/// Flattens the model into a row.
+pub fn flatten<T: Serialize>(model: &T) -> Result<Row> {
+    let mut row = Row::new();
+    flatten_into(&serde_json::to_value(model)?, String::new(), &mut row);
+    Ok(row)
+}
+
+fn flatten_into(value: &Value, path: String, row: &mut Row) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+
+    match value {
+        Value::Object(map) if is_reference(map) => {
+            row.insert(path, map["name"].clone());
+        }
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten_into(value, join(key), row);
+            }
+        }
+        Value::Array(values) if values.iter().all(is_scalar_or_reference) => {
+            let joined: Vec<String> = values.iter().map(scalar).collect();
+            row.insert(path, Value::String(joined.join(";")));
+        }
+        Value::Array(values) => match pivot(values) {
+            Some(columns) => {
+                for (key, value) in columns {
+                    row.insert(join(&key), value);
+                }
+            }
+            None => {
+                row.insert(path, Value::String(value.to_string()));
+            }
+        },
+        value => {
+            row.insert(path, value.clone());
+        }
+    }
+}
+
+/// Turns a list of entries with a single reference and scalar fields into columns
+/// named after the references, `None` when the list does not have that shape.
+fn pivot(values: &[Value]) -> Option<Vec<(String, Value)>> {
+    let mut columns = Vec::new();
+
+    for value in values {
+        let map = value.as_object()?;
+        let mut references = map.values().filter_map(|v| match v {
+            Value::Object(reference) if is_reference(reference) => Some(reference),
+            _ => None,
+        });
+        let name = scalar(&references.next()?["name"]);
+        if references.next().is_some() {
+            return None;
+        }
+
+        let fields: Vec<(&String, &Value)> = map
+            .iter()
+            .filter(|(_, v)| !matches!(v, Value::Object(r) if is_reference(r)))
+            .collect();
+        if fields.is_empty() || fields.iter().any(|(_, v)| !is_scalar(v)) {
+            return None;
+        }
+
+        for (key, value) in fields.iter() {
+            let column = if fields.len() == 1 {
+                name.clone()
+            } else {
+                format!("{}.{}", name, key)
+            };
+            if columns.iter().any(|(c, _)| *c == column) {
+                return None;
+            }
+            columns.push((column, (*value).clone()));
+        }
+    }
+
+    Some(columns)
+}
+
+fn is_reference(map: &Map<String, Value>) -> bool {
+    map.len() == 2 && map.contains_key("name") && map.contains_key("url")
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Object(_) | Value::Array(_))
+}
+
+fn is_scalar_or_reference(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => is_reference(map),
+        value => is_scalar(value),
+    }
+}
+
+/// Text of a scalar value or the name of a reference.
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        Value::Object(map) if is_reference(map) => scalar(&map["name"]),
+        value => value.to_string(),
+    }
+}
+
+/// Flattened models stored column by column.
+/// Columns are the union of the columns of all rows, missing values are `null`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Table {
+    columns: Vec<String>,
+    values: Vec<Vec<Value>>,
+    len: usize,
+}
+
+impl Table {
+    /// Creates empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Flattens the model and appends it as a row.
+    pub fn push<T: Serialize>(&mut self, model: &T) -> Result<()> {
+        self.push_row(flatten(model)?);
+        Ok(())
+    }
+
+    /// Appends the row, adding its new columns.
+    pub fn push_row(&mut self, mut row: Row) {
+        for (column, values) in self.columns.iter().zip(self.values.iter_mut()) {
+            values.push(row.remove(column).unwrap_or(Value::Null));
+        }
+        for (column, value) in row {
+            let mut values = vec![Value::Null; self.len];
+            values.push(value);
+            self.columns.push(column);
+            self.values.push(values);
+        }
+        self.len += 1;
+    }
+
+    /// Names of the columns, in the order they were first seen.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Values of the column.
+    pub fn column(&self, name: &str) -> Option<&[Value]> {
+        self.columns
+            .iter()
+            .position(|column| column == name)
+            .map(|index| self.values[index].as_slice())
+    }
+
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the table has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Row at the index.
+    pub fn row(&self, index: usize) -> Option<Row> {
+        if index >= self.len {
+            return None;
+        }
+
+        Some(
+            self.columns
+                .iter()
+                .zip(self.values.iter())
+                .map(|(column, values)| (column.clone(), values[index].clone()))
+                .collect(),
+        )
+    }
+
+    /// Writes the table in the format.
+    pub fn write<W: Write>(&self, format: ExportFormat, out: W) -> Result<()> {
+        match format {
+            ExportFormat::Csv => self.write_csv(out),
+            ExportFormat::JsonLines => self.write_json_lines(out),
+            ExportFormat::Columnar => self.write_columnar(out),
+        }
+    }
+
+    /// Writes the table as CSV with a header row.
+    pub fn write_csv<W: Write>(&self, mut out: W) -> Result<()> {
+        write_csv_record(&mut out, self.columns.iter().map(String::as_str))?;
+        for index in 0..self.len {
+            let fields: Vec<String> = self.values.iter().map(|v| scalar(&v[index])).collect();
+            write_csv_record(&mut out, fields.iter().map(String::as_str))?;
+        }
+        Ok(())
+    }
+
+    /// Writes every row as a JSON object on its own line.
+    pub fn write_json_lines<W: Write>(&self, mut out: W) -> Result<()> {
+        for index in 0..self.len {
+            serde_json::to_writer(&mut out, &self.row(index))?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Writes a JSON object mapping every column to its values.
+    pub fn write_columnar<W: Write>(&self, out: W) -> Result<()> {
+        let columns: Map<String, Value> = self
+            .columns
+            .iter()
+            .zip(self.values.iter())
+            .map(|(column, values)| (column.clone(), Value::Array(values.clone())))
+            .collect();
+        serde_json::to_writer(out, &columns)?;
+        Ok(())
+    }
+}
+
+fn write_csv_record<'a, W: Write, I: Iterator<Item = &'a str>>(
+    out: &mut W,
+    fields: I,
+) -> Result<()> {
+    let fields: Vec<String> = fields
+        .map(|field| {
+            if field.contains(&[',', '"', '\n', '\r'][..]) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    writeln!(out, "{}", fields.join(","))?;
+    Ok(())
+}
+
+/// Resources of an endpoint fetched one at a time.
+pub struct ResourceStream<M> {
+    resources: VecDeque<Box<dyn ApiNamedResource<ResourceType = M>>>,
+}
+
+impl<M> ResourceStream<M> {
+    /// Fetches the list of all resources of the endpoint.
+    pub async fn new<E>(endpoint: &E) -> Result<Self>
+    where
+        E: ApiEndpoint<Model = M> + Sync,
+        E::NamedResourceList: ApiNamedResourceList<ResourceType = M>,
+    {
+        let list = endpoint.all_paginated(0, MAX_PAGINATION_LIMIT).await?;
+        Ok(Self {
+            resources: list.resources().into_iter().collect(),
+        })
+    }
+
+    /// Number of resources not fetched yet.
+    pub fn remaining(&self) -> usize {
+        self.resources.len()
+    }
+
+    /// Fetches the next resource, `None` when all of them were fetched.
+    pub async fn next(&mut self) -> Option<Result<M>> {
+        let resource = self.resources.pop_front()?;
+        Some(resource.get().await)
+    }
+}
+
+/// Exports every resource of the endpoint, returning the number of exported resources.
+///
+/// JSON Lines are written as the resources arrive. CSV and columnar output need all
+/// columns up front, so those resources are collected into a `Table` first.
+pub async fn export_endpoint<E, W>(endpoint: &E, format: ExportFormat, mut out: W) -> Result<usize>
+where
+    E: ApiEndpoint + Sync,
+    E::Model: Serialize,
+    E::NamedResourceList: ApiNamedResourceList<ResourceType = E::Model>,
+    W: Write,
+{
+    let mut stream = ResourceStream::new(endpoint).await?;
+    let mut table = Table::new();
+    let mut count = 0;
+
+    while let Some(model) = stream.next().await {
+        let row = flatten(&model?)?;
+        if format == ExportFormat::JsonLines {
+            serde_json::to_writer(&mut out, &row)?;
+            out.write_all(b"\n")?;
+        } else {
+            table.push_row(row);
+        }
+        count += 1;
+    }
+
+    if format != ExportFormat::JsonLines {
+        table.write(format, out)?;
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{export_endpoint, flatten, ExportFormat, Table};
+    use crate::testing::MockServer;
+    use serde_json::{json, Value};
+
+    fn berry(id: usize, name: &str, spicy: i32, dry: i32) -> Value {
+        json!({
+            "id": id,
+            "name": name,
+            "growth_time": 3,
+            "max_harvest": 5,
+            "natural_gift_power": 60,
+            "size": 20,
+            "smoothness": 25,
+            "soil_dryness": 15,
+            "firmness": { "name": "soft", "url": "https://pokeapi.co/api/v2/berry-firmness/2/" },
+            "flavors": [
+                { "potency": spicy, "flavor": { "name": "spicy", "url": "https://pokeapi.co/api/v2/berry-flavor/1/" } },
+                { "potency": dry, "flavor": { "name": "dry", "url": "https://pokeapi.co/api/v2/berry-flavor/2/" } }
+            ],
+            "item": { "name": format!("{}-berry", name), "url": "https://pokeapi.co/api/v2/item/126/" },
+            "natural_gift_type": { "name": "fire", "url": "https://pokeapi.co/api/v2/type/10/" }
+        })
+    }
+
+    #[test]
+    fn flattens_models() {
+        let berry: crate::models::v2::berry::Berry =
+            serde_json::from_value(berry(1, "cheri", 10, 0)).unwrap();
+        let row = flatten(&berry).unwrap();
+
+        assert_eq!(row["name"], "cheri");
+        assert_eq!(row["firmness"], "soft");
+        assert_eq!(row["flavors.spicy"], 10);
+        assert_eq!(row["flavors.dry"], 0);
+        assert_eq!(row["natural_gift_type"], "fire");
+
+        let row = flatten(&json!({
+            "names": [
+                { "name": "Cheri", "language": { "name": "en", "url": "" } },
+                { "name": "Ceriz", "language": { "name": "fr", "url": "" } }
+            ],
+            "berries": [{ "name": "cheri", "url": "" }, { "name": "pecha", "url": "" }],
+            "nested": { "value": null, "list": [[1]] }
+        }))
+        .unwrap();
+
+        assert_eq!(row["names.fr"], "Ceriz");
+        assert_eq!(row["berries"], "cheri;pecha");
+        assert_eq!(row["nested.value"], Value::Null);
+        assert_eq!(row["nested.list"], "[[1]]");
+    }
+
+    #[test]
+    fn writes_formats() {
+        let mut table = Table::new();
+        table
+            .push(&json!({ "name": "cheri", "note": "red, \"spicy\"" }))
+            .unwrap();
+        table
+            .push(&json!({ "name": "chesto", "size": 80 }))
+            .unwrap();
+
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.columns(), ["name", "note", "size"]);
+        assert_eq!(table.column("size").unwrap(), [Value::Null, json!(80)]);
+
+        let mut csv = Vec::new();
+        table.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "name,note,size\ncheri,\"red, \"\"spicy\"\"\",\nchesto,,80\n"
+        );
+
+        let mut lines = Vec::new();
+        table.write_json_lines(&mut lines).unwrap();
+        let lines = String::from_utf8(lines).unwrap();
+        assert_eq!(lines.lines().count(), 2);
+        assert_eq!(
+            serde_json::from_str::<Value>(lines.lines().nth(1).unwrap()).unwrap()["size"],
+            80
+        );
+
+        let mut columnar = Vec::new();
+        table.write_columnar(&mut columnar).unwrap();
+        let columnar: Value = serde_json::from_slice(&columnar).unwrap();
+        assert_eq!(columnar["name"], json!(["cheri", "chesto"]));
+    }
+
+    #[tokio::test]
+    async fn exports_endpoint() {
+        let server = MockServer::start().unwrap();
+        server.add_resource("berry", berry(1, "cheri", 10, 0));
+        server.add_resource("berry", berry(2, "chesto", 0, 10));
+        let client = server.client().unwrap();
+
+        let mut csv = Vec::new();
+        let count = export_endpoint(&client.berries(), ExportFormat::Csv, &mut csv)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+        let spicy = header.iter().position(|c| *c == "flavors.spicy").unwrap();
+        let cheri: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(cheri[spicy], "10");
+
+        let mut jsonl = Vec::new();
+        export_endpoint(&client.berries(), ExportFormat::JsonLines, &mut jsonl)
+            .await
+            .unwrap();
+        let first: Value =
+            serde_json::from_str(String::from_utf8(jsonl).unwrap().lines().next().unwrap())
+                .unwrap();
+        assert_eq!(first["item"], "cheri-berry");
+    }
+}
@@ -0,0 +1,584 @@
+//! Client of the [PokeAPI GraphQL beta](https://beta.pokeapi.co/graphql/console/).
+//!
+//! A single GraphQL query can fetch related data that takes several REST requests,
+//! e.g. berries with their firmness and flavors. Raw queries return the `data` document,
+//! typed queries convert it into `models::v2` structs where the shapes match:
+//!
+//! ```no_run
+//! # async fn example() -> pokemon_api::Result<()> {
+//! use pokemon_api::graphql::{BerriesQuery, GraphQlClient};
+//!
+//! let client = GraphQlClient::new()?;
+//! for berry in client.run(&BerriesQuery::new().with_limit(5)).await? {
+//!     println!("{} is {}", berry.name, berry.firmness.name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use reqwest::Client as ReqClient;
+use reqwest::ClientBuilder;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::api::{POKE_API_BASE_URL, POKE_API_CLIENT_NAME};
+use crate::models::v2::berry::{Berry, BerryFlavorMap};
+use crate::models::v2::common::{Id, Integer};
+use crate::models::v2::resource::{NamedResource, Resource};
+use crate::{Error, Result};
+
+/// Url of the public GraphQL endpoint.
+pub const POKE_API_GRAPHQL_URL: &str = "https://beta.pokeapi.co/graphql/v1beta";
+
+/// GraphQL request document.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Query {
+    /// Query text.
+    pub query: String,
+
+    /// Values of the query variables.
+    pub variables: Value,
+}
+
+impl Query {
+    /// Creates query without variables.
+    pub fn new<T: Into<String>>(query: T) -> Self {
+        Self {
+            query: query.into(),
+            variables: json!({}),
+        }
+    }
+
+    /// Sets the query variables, e.g. `json!({ "name": "cheri" })`.
+    pub fn with_variables(mut self, variables: Value) -> Self {
+        self.variables = variables;
+        self
+    }
+}
+
+/// Query converting its result into typed values.
+pub trait TypedQuery {
+    /// Type of the result.
+    type Output;
+
+    /// Request document of the query.
+    fn query(&self) -> Query;
+
+    /// Converts the `data` document of the response.
+    /// References are built under `api_url`, like `https://pokeapi.co/api/v2`.
+    fn parse(&self, data: Value, api_url: &str) -> Result<Self::Output>;
+}
+
+#[derive(Deserialize)]
+struct Response {
+    data: Option<Value>,
+    #[serde(default)]
+    errors: Vec<ResponseError>,
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+    message: String,
+}
+
+/// Client sending queries to the GraphQL endpoint.
+#[derive(Clone)]
+pub struct GraphQlClient {
+    client: ReqClient,
+    url: String,
+    api_url: String,
+}
+
+impl GraphQlClient {
+    /// Initializes client of the public endpoint.
+    pub fn new() -> Result<Self> {
+        let builder = ClientBuilder::new().user_agent(POKE_API_CLIENT_NAME);
+
+        Ok(Self {
+            client: builder.build()?,
+            url: POKE_API_GRAPHQL_URL.to_string(),
+            api_url: format!("{}/v2", POKE_API_BASE_URL),
+        })
+    }
+
+    /// Sends queries to the endpoint at the given url instead of the public one.
+    pub fn with_url<T: Into<String>>(mut self, url: T) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Builds references of typed results under the REST API at the given url,
+    /// like `https://pokeapi.co/api`.
+    pub fn with_api_url<T: Into<String>>(mut self, url: T) -> Self {
+        self.api_url = url.into().trim_end_matches('/').to_string() + "/v2";
+        self
+    }
+
+    /// Sends the query and returns the `data` document of the response.
+    /// Errors reported by the endpoint result in `Error::GraphQlError`.
+    pub async fn query(&self, query: &Query) -> Result<Value> {
+        let response: Response = self
+            .client
+            .post(&self.url)
+            .json(query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if !response.errors.is_empty() {
+            let messages: Vec<String> = response.errors.into_iter().map(|e| e.message).collect();
+            return Err(Error::GraphQlError(messages.join("; ")));
+        }
+        response
+            .data
+            .ok_or_else(|| Error::GraphQlError("response has no data".into()))
+    }
+
+    /// Sends the query and casts the `data` document to the type `T`.
+    pub async fn query_as<T: DeserializeOwned>(&self, query: &Query) -> Result<T> {
+        Ok(serde_json::from_value(self.query(query).await?)?)
+    }
+
+    /// Sends the typed query and converts its result.
+    pub async fn run<Q: TypedQuery>(&self, query: &Q) -> Result<Q::Output> {
+        let data = self.query(&query.query()).await?;
+        query.parse(data, &self.api_url)
+    }
+}
+
+/// Named row of the GraphQL schema, converted into `NamedResource`.
+#[derive(Deserialize)]
+struct Row {
+    id: Id,
+    name: String,
+}
+
+impl Row {
+    fn reference(&self, api_url: &str, endpoint: &str) -> NamedResource {
+        reference(api_url, endpoint, self.id, &self.name)
+    }
+}
+
+fn reference(api_url: &str, endpoint: &str, id: Id, name: &str) -> NamedResource {
+    NamedResource {
+        name: name.to_string(),
+        url: format!("{}/{}/{}/", api_url, endpoint, id),
+    }
+}
+
+const BERRIES_QUERY: &str = "query Berries($where: pokemon_v2_berry_bool_exp, $limit: Int) {
+  pokemon_v2_berry(where: $where, limit: $limit, order_by: {id: asc}) {
+    id
+    name
+    growth_time
+    max_harvest
+    natural_gift_power
+    size
+    smoothness
+    soil_dryness
+    pokemon_v2_berryfirmness { id name }
+    pokemon_v2_item { id name }
+    pokemon_v2_type { id name }
+    pokemon_v2_berryflavormaps(order_by: {berry_flavor_id: asc}) {
+      potency
+      pokemon_v2_berryflavor { id name }
+    }
+  }
+}";
+
+/// Berries with their firmness, flavors, item and natural gift type, as `Berry` models.
+#[derive(Clone, Debug, Default)]
+pub struct BerriesQuery {
+    names: Vec<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct BerryRow {
+    id: Id,
+    name: String,
+    growth_time: Integer,
+    max_harvest: Integer,
+    natural_gift_power: Integer,
+    size: Integer,
+    smoothness: Integer,
+    soil_dryness: Integer,
+    pokemon_v2_berryfirmness: Row,
+    pokemon_v2_item: Row,
+    pokemon_v2_type: Row,
+    pokemon_v2_berryflavormaps: Vec<BerryFlavorRow>,
+}
+
+#[derive(Deserialize)]
+struct BerryFlavorRow {
+    potency: Integer,
+    pokemon_v2_berryflavor: Row,
+}
+
+impl BerriesQuery {
+    /// Creates query of all berries.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the query to the berries with the given names.
+    pub fn with_names<S: AsRef<str>>(mut self, names: &[S]) -> Self {
+        self.names = names.iter().map(|n| n.as_ref().to_string()).collect();
+        self
+    }
+
+    /// Returns at most `limit` berries.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+impl TypedQuery for BerriesQuery {
+    type Output = Vec<Berry>;
+
+    fn query(&self) -> Query {
+        let filter = if self.names.is_empty() {
+            json!({})
+        } else {
+            json!({ "name": { "_in": self.names } })
+        };
+
+        Query::new(BERRIES_QUERY).with_variables(json!({
+            "where": filter,
+            "limit": self.limit,
+        }))
+    }
+
+    fn parse(&self, data: Value, api_url: &str) -> Result<Vec<Berry>> {
+        let rows: Vec<BerryRow> = serde_json::from_value(data["pokemon_v2_berry"].clone())?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Berry {
+                id: row.id,
+                name: row.name,
+                growth_time: row.growth_time,
+                max_harvest: row.max_harvest,
+                natural_gift_power: row.natural_gift_power,
+                size: row.size,
+                smoothness: row.smoothness,
+                soil_dryness: row.soil_dryness,
+                firmness: row
+                    .pokemon_v2_berryfirmness
+                    .reference(api_url, "berry-firmness"),
+                flavors: row
+                    .pokemon_v2_berryflavormaps
+                    .into_iter()
+                    .map(|flavor| BerryFlavorMap {
+                        potency: flavor.potency,
+                        flavor: flavor
+                            .pokemon_v2_berryflavor
+                            .reference(api_url, "berry-flavor"),
+                    })
+                    .collect(),
+                item: row.pokemon_v2_item.reference(api_url, "item"),
+                natural_gift_type: row.pokemon_v2_type.reference(api_url, "type"),
+            })
+            .collect())
+    }
+}
+
+const SPECIES_EVOLUTIONS_QUERY: &str = "query SpeciesEvolutions($name: String!) {
+  pokemon_v2_pokemonspecies(where: {name: {_eq: $name}}) {
+    id
+    name
+    is_baby
+    is_legendary
+    is_mythical
+    evolves_from_species_id
+    pokemon_v2_evolutionchain {
+      id
+      pokemon_v2_pokemonspecies(order_by: {order: asc}) {
+        id
+        name
+        is_baby
+        evolves_from_species_id
+      }
+    }
+  }
+}";
+
+/// Pokémon species with the members of its evolution chain.
+#[derive(Clone, Debug)]
+pub struct SpeciesEvolutionsQuery {
+    name: String,
+}
+
+/// Pokémon species and its evolution family.
+#[derive(Clone, Debug, Serialize)]
+pub struct SpeciesEvolutions {
+    /// The queried species.
+    pub species: NamedResource,
+
+    /// Whether this is a baby Pokémon.
+    pub is_baby: bool,
+
+    /// Whether this is a legendary Pokémon.
+    pub is_legendary: bool,
+
+    /// Whether this is a mythical Pokémon.
+    pub is_mythical: bool,
+
+    /// The species that evolves into this species.
+    pub evolves_from_species: Option<NamedResource>,
+
+    /// The evolution chain this species is a member of.
+    pub evolution_chain: Option<Resource>,
+
+    /// Species of the evolution chain, ordered like the national Pokédex families.
+    pub family: Vec<EvolutionMember>,
+}
+
+/// Species of the evolution chain.
+#[derive(Clone, Debug, Serialize)]
+pub struct EvolutionMember {
+    /// The member species.
+    pub species: NamedResource,
+
+    /// Whether this is a baby Pokémon.
+    pub is_baby: bool,
+
+    /// The species that evolves into this member.
+    pub evolves_from_species: Option<NamedResource>,
+}
+
+#[derive(Deserialize)]
+struct SpeciesRow {
+    id: Id,
+    name: String,
+    #[serde(default)]
+    is_baby: bool,
+    #[serde(default)]
+    is_legendary: bool,
+    #[serde(default)]
+    is_mythical: bool,
+    evolves_from_species_id: Option<Id>,
+    pokemon_v2_evolutionchain: Option<ChainRow>,
+}
+
+#[derive(Deserialize)]
+struct ChainRow {
+    id: Id,
+    pokemon_v2_pokemonspecies: Vec<SpeciesRow>,
+}
+
+impl SpeciesEvolutionsQuery {
+    /// Creates query of the species with the name.
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+impl TypedQuery for SpeciesEvolutionsQuery {
+    type Output = Option<SpeciesEvolutions>;
+
+    fn query(&self) -> Query {
+        Query::new(SPECIES_EVOLUTIONS_QUERY).with_variables(json!({ "name": self.name }))
+    }
+
+    fn parse(&self, data: Value, api_url: &str) -> Result<Option<SpeciesEvolutions>> {
+        let rows: Vec<SpeciesRow> =
+            serde_json::from_value(data["pokemon_v2_pokemonspecies"].clone())?;
+        let row = match rows.into_iter().next() {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+
+        let members = row
+            .pokemon_v2_evolutionchain
+            .as_ref()
+            .map(|chain| chain.pokemon_v2_pokemonspecies.as_slice())
+            .unwrap_or_default();
+        let species = |id: Option<Id>| {
+            members
+                .iter()
+                .find(|member| Some(member.id) == id)
+                .map(|member| reference(api_url, "pokemon-species", member.id, &member.name))
+        };
+
+        Ok(Some(SpeciesEvolutions {
+            species: reference(api_url, "pokemon-species", row.id, &row.name),
+            is_baby: row.is_baby,
+            is_legendary: row.is_legendary,
+            is_mythical: row.is_mythical,
+            evolves_from_species: species(row.evolves_from_species_id),
+            evolution_chain: row
+                .pokemon_v2_evolutionchain
+                .as_ref()
+                .map(|chain| Resource {
+                    url: format!("{}/evolution-chain/{}/", api_url, chain.id),
+                }),
+            family: members
+                .iter()
+                .map(|member| EvolutionMember {
+                    species: reference(api_url, "pokemon-species", member.id, &member.name),
+                    is_baby: member.is_baby,
+                    evolves_from_species: species(member.evolves_from_species_id),
+                })
+                .collect(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BerriesQuery, GraphQlClient, Query, SpeciesEvolutionsQuery};
+    use crate::testing::MockServer;
+    use crate::Error;
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    const PATH: &str = "/graphql/v1beta";
+
+    fn client(server: &MockServer) -> GraphQlClient {
+        GraphQlClient::new().unwrap().with_url(server.url(PATH))
+    }
+
+    #[tokio::test]
+    async fn queries_berries() {
+        let server = MockServer::start().unwrap();
+        let received = Arc::new(Mutex::new(Value::Null));
+        let sink = received.clone();
+        server.add_post_handler(PATH, move |body| {
+            *sink.lock().unwrap() = body.clone();
+            json!({ "data": { "pokemon_v2_berry": [{
+                "id": 1,
+                "name": "cheri",
+                "growth_time": 3,
+                "max_harvest": 5,
+                "natural_gift_power": 60,
+                "size": 20,
+                "smoothness": 25,
+                "soil_dryness": 15,
+                "pokemon_v2_berryfirmness": { "id": 2, "name": "soft" },
+                "pokemon_v2_item": { "id": 126, "name": "cheri-berry" },
+                "pokemon_v2_type": { "id": 10, "name": "fire" },
+                "pokemon_v2_berryflavormaps": [
+                    { "potency": 10, "pokemon_v2_berryflavor": { "id": 1, "name": "spicy" } }
+                ]
+            }]}})
+        });
+
+        let berries = client(&server)
+            .run(&BerriesQuery::new().with_names(&["cheri"]).with_limit(1))
+            .await
+            .unwrap();
+        assert_eq!(berries[0].name, "cheri");
+        assert_eq!(
+            berries[0].firmness.url,
+            "https://pokeapi.co/api/v2/berry-firmness/2/"
+        );
+        assert_eq!(berries[0].flavors[0].flavor.name, "spicy");
+        assert_eq!(berries[0].natural_gift_type.name, "fire");
+
+        let body = received.lock().unwrap().clone();
+        assert!(body["query"]
+            .as_str()
+            .unwrap()
+            .contains("pokemon_v2_berry("));
+        assert_eq!(
+            body["variables"],
+            json!({ "where": { "name": { "_in": ["cheri"] } }, "limit": 1 })
+        );
+        assert_eq!(server.requests(), vec![PATH]);
+    }
+
+    #[tokio::test]
+    async fn queries_species_evolutions() {
+        let server = MockServer::start().unwrap();
+        server.add_post_handler(PATH, |body| {
+            let species = |id: i32, name: &str, from: Option<i32>| {
+                json!({ "id": id, "name": name, "is_baby": false, "evolves_from_species_id": from })
+            };
+            let rows = if body["variables"]["name"] == "ivysaur" {
+                json!([{
+                    "id": 2,
+                    "name": "ivysaur",
+                    "is_baby": false,
+                    "is_legendary": false,
+                    "is_mythical": false,
+                    "evolves_from_species_id": 1,
+                    "pokemon_v2_evolutionchain": {
+                        "id": 1,
+                        "pokemon_v2_pokemonspecies": [
+                            species(1, "bulbasaur", None),
+                            species(2, "ivysaur", Some(1)),
+                            species(3, "venusaur", Some(2))
+                        ]
+                    }
+                }])
+            } else {
+                json!([])
+            };
+            json!({ "data": { "pokemon_v2_pokemonspecies": rows } })
+        });
+        let client = client(&server).with_api_url("http://localhost:8000/api");
+
+        let ivysaur = client
+            .run(&SpeciesEvolutionsQuery::new("ivysaur"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ivysaur.evolves_from_species.unwrap().name, "bulbasaur");
+        assert_eq!(
+            ivysaur.evolution_chain.unwrap().url,
+            "http://localhost:8000/api/v2/evolution-chain/1/"
+        );
+        let family: Vec<(&str, Option<&str>)> = ivysaur
+            .family
+            .iter()
+            .map(|m| {
+                (
+                    m.species.name.as_str(),
+                    m.evolves_from_species.as_ref().map(|s| s.name.as_str()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            family,
+            vec![
+                ("bulbasaur", None),
+                ("ivysaur", Some("bulbasaur")),
+                ("venusaur", Some("ivysaur"))
+            ]
+        );
+
+        assert!(client
+            .run(&SpeciesEvolutionsQuery::new("missingno"))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn reports_errors() {
+        let server = MockServer::start().unwrap();
+        server.add_post_handler(
+            PATH,
+            |_| json!({ "errors": [{ "message": "field \"foo\" not found" }] }),
+        );
+
+        let err = client(&server)
+            .query(&Query::new("{ foo }"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::GraphQlError(ref m) if m.contains("foo")));
+
+        let err = GraphQlClient::new()
+            .unwrap()
+            .with_url(server.url("/unknown"))
+            .query(&Query::new("{ foo }"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("404"));
+    }
+}
@@ -0,0 +1,378 @@
+//! Minimal HTTP/1.1 server plumbing shared by `MockServer` and the local API server.
+//!
+//! Every connection is served by its own thread and closed after a single response.
+//! Connections past [`MAX_CONNECTIONS`] are answered with `503 Service Unavailable` right away,
+//! and request bodies larger than [`MAX_BODY_SIZE`] with `413 Payload Too Large`.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::api::POKE_API_BASE_URL;
+use crate::mirror::rewrite_urls;
+
+/// Page size used when the request does not specify the limit, same as PokeAPI.
+pub(crate) const DEFAULT_LIMIT: usize = 20;
+
+/// Number of connections served at the same time, each by its own thread.
+const MAX_CONNECTIONS: usize = 64;
+
+/// Largest request body read, in bytes.
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Time to wait for the client to send the request or read the response.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Request received by the server.
+pub(crate) struct Request {
+    pub method: String,
+    /// Path with the query, like `/api/v2/berry/?offset=20`.
+    pub target: String,
+    /// Value of the `Host` header, if sent.
+    #[cfg_attr(not(feature = "server"), allow(dead_code))]
+    pub host: Option<String>,
+    /// Body of `POST` requests, only answered by `MockServer`.
+    #[cfg_attr(not(any(test, feature = "testing")), allow(dead_code))]
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Target without the query.
+    pub fn path(&self) -> &str {
+        self.target.split('?').next().unwrap_or_default()
+    }
+
+    /// Query of the target, empty if there is none.
+    pub fn query(&self) -> &str {
+        self.target
+            .split_once('?')
+            .map(|(_, query)| query)
+            .unwrap_or_default()
+    }
+}
+
+/// HTTP response produced by the server.
+pub(crate) struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn json(value: &Value) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json; charset=utf-8",
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    #[cfg_attr(not(any(test, feature = "testing")), allow(dead_code))]
+    pub fn file(body: &[u8]) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/octet-stream",
+            body: body.to_vec(),
+        }
+    }
+
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            ..Self::json(&serde_json::json!({ "detail": reason(status) }))
+        }
+    }
+}
+
+/// Function producing the response to a request.
+pub(crate) type Handler = Arc<dyn Fn(&Request) -> Response + Send + Sync>;
+
+/// Accepts connections of the listener on a separate thread until `stopped` is set
+/// and the listener is woken up by one more connection.
+pub(crate) fn serve(
+    listener: TcpListener,
+    stopped: Arc<AtomicBool>,
+    handler: Handler,
+) -> JoinHandle<()> {
+    let active = Arc::new(AtomicUsize::new(0));
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if stopped.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+            let _ = stream.set_read_timeout(Some(TIMEOUT));
+            let _ = stream.set_write_timeout(Some(TIMEOUT));
+
+            if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                active.fetch_sub(1, Ordering::SeqCst);
+                let _ = write_response(&mut stream, &Response::status(503));
+                let _ = stream.shutdown(Shutdown::Both);
+                continue;
+            }
+
+            let handler = handler.clone();
+            let active = active.clone();
+            thread::spawn(move || {
+                handle(stream, &handler);
+                active.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+    })
+}
+
+/// Serves a single request. Connections are closed after the response.
+fn handle(mut stream: TcpStream, handler: &Handler) {
+    let response = match read_request(&stream) {
+        Ok(request) => handler(&request),
+        Err(status) => Response::status(status),
+    };
+    let _ = write_response(&mut stream, &response);
+    let _ = stream.shutdown(Shutdown::Both);
+}
+
+/// Reads the request line, the headers and the body of the request,
+/// or gives the status of the error response.
+fn read_request(stream: &TcpStream) -> Result<Request, u16> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|_| 400u16)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().ok_or(400u16)?.to_string();
+    let target = parts.next().ok_or(400u16)?.to_string();
+
+    let mut host = None;
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).map_err(|_| 400u16)? == 0 || header.trim().is_empty() {
+            break;
+        }
+
+        let mut parts = header.splitn(2, ':');
+        if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+            let name = name.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            } else if name.eq_ignore_ascii_case("host") {
+                host = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    if content_length > MAX_BODY_SIZE {
+        return Err(413);
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(|_| 400u16)?;
+
+    Ok(Request {
+        method,
+        target,
+        host,
+        body,
+    })
+}
+
+/// Writes the response. Any origin may read it, so browser applications can use the server.
+fn write_response(stream: &mut TcpStream, response: &Response) -> std::io::Result<()> {
+    let retry_after = if response.status == 429 {
+        "Retry-After: 1\r\n"
+    } else {
+        ""
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n{}Connection: close\r\n\r\n",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        response.body.len(),
+        retry_after
+    )?;
+    stream.write_all(&response.body)?;
+    stream.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Error",
+    }
+}
+
+/// Builds the page of the resource list with `count`, `next` and `previous` like PokeAPI does,
+/// given `(id, name)` of every resource of the endpoint in order.
+/// Resources without a name are listed under their id. Like PokeAPI, a zero or invalid limit
+/// gives the default page size.
+pub(crate) fn page(resources: &[(i64, String)], endpoint: &str, query: &str, base: &str) -> Value {
+    let mut offset = 0;
+    let mut limit = DEFAULT_LIMIT;
+    for pair in query.split('&') {
+        let mut parts = pair.splitn(2, '=');
+        match (parts.next(), parts.next().and_then(|v| v.parse().ok())) {
+            (Some("offset"), Some(value)) => offset = value,
+            (Some("limit"), Some(value)) if value > 0 => limit = value,
+            _ => {}
+        }
+    }
+
+    let count = resources.len();
+    let page_url = |offset: usize| {
+        format!(
+            "{}/v2/{}/?offset={}&limit={}",
+            base, endpoint, offset, limit
+        )
+    };
+
+    let next = if offset.saturating_add(limit) < count {
+        Value::String(page_url(offset + limit))
+    } else {
+        Value::Null
+    };
+    let previous = if offset > 0 {
+        Value::String(page_url(offset.saturating_sub(limit)))
+    } else {
+        Value::Null
+    };
+
+    let results: Vec<Value> = resources
+        .iter()
+        .skip(offset)
+        .take(limit)
+        .map(|(id, name)| {
+            serde_json::json!({
+                "name": name,
+                "url": format!("{}/v2/{}/{}/", base, endpoint, id),
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "count": count,
+        "next": next,
+        "previous": previous,
+        "results": results,
+    })
+}
+
+/// Points urls of the document at the server, whether they point at PokeAPI
+/// or are host relative like the urls of a mirror written without a host.
+pub(crate) fn rewrite(value: &mut Value, base: &str) {
+    let target = format!("{}/v2", base);
+    rewrite_urls(value, &format!("{}/v2", POKE_API_BASE_URL), &target);
+    rewrite_urls(value, "/api/v2", &target);
+}
+
+/// `(id, name)` of the resource document, the id standing in for a missing name.
+pub(crate) fn resource_key(resource: &Value) -> (i64, String) {
+    let id = resource["id"].as_i64().unwrap_or_default();
+    let name = match resource["name"].as_str() {
+        Some(name) => name.to_string(),
+        None => id.to_string(),
+    };
+    (id, name)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use super::{page, serve, Handler, Response, DEFAULT_LIMIT, MAX_BODY_SIZE, MAX_CONNECTIONS};
+
+    const BASE: &str = "http://localhost/api";
+
+    fn resources(count: i64) -> Vec<(i64, String)> {
+        (1..=count)
+            .map(|id| (id, format!("berry-{}", id)))
+            .collect()
+    }
+
+    #[test]
+    fn pages_resources() {
+        let list = page(&resources(5), "berry", "offset=2&limit=2", BASE);
+        assert_eq!(list["count"], 5);
+        assert_eq!(list["results"][0]["name"], "berry-3");
+        assert_eq!(
+            list["next"],
+            "http://localhost/api/v2/berry/?offset=4&limit=2"
+        );
+        assert_eq!(
+            list["previous"],
+            "http://localhost/api/v2/berry/?offset=0&limit=2"
+        );
+    }
+
+    #[test]
+    fn pages_with_extreme_limits() {
+        let list = page(&resources(25), "berry", "limit=0", BASE);
+        assert_eq!(list["results"].as_array().unwrap().len(), DEFAULT_LIMIT);
+        assert_eq!(
+            list["next"],
+            "http://localhost/api/v2/berry/?offset=20&limit=20"
+        );
+
+        let query = format!("offset=1&limit={}", usize::MAX);
+        let list = page(&resources(25), "berry", &query, BASE);
+        assert_eq!(list["results"].as_array().unwrap().len(), 24);
+        assert!(list["next"].is_null());
+    }
+
+    fn status_line(stream: &mut TcpStream) -> String {
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response.lines().next().unwrap_or_default().to_string()
+    }
+
+    #[test]
+    fn limits_bodies_and_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let stopped = Arc::new(AtomicBool::new(false));
+        let handler: Handler = Arc::new(|_| Response::status(200));
+        let thread = serve(listener, stopped.clone(), handler);
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "POST /graphql HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_SIZE + 1
+        )
+        .unwrap();
+        assert_eq!(status_line(&mut stream), "HTTP/1.1 413 Payload Too Large");
+
+        // Idle connections hold every handler thread until they are closed.
+        let idle: Vec<TcpStream> = (0..MAX_CONNECTIONS)
+            .map(|_| TcpStream::connect(addr).unwrap())
+            .collect();
+        let mut stream = TcpStream::connect(addr).unwrap();
+        assert_eq!(status_line(&mut stream), "HTTP/1.1 503 Service Unavailable");
+        drop(idle);
+
+        stopped.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(addr);
+        thread.join().unwrap();
+    }
+}
@@ -1,6 +1,23 @@
-mod api;
+pub mod api;
+pub mod calc;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod crawler;
 mod error;
-mod models;
+pub mod export;
+pub mod graphql;
+#[cfg(any(test, feature = "testing", feature = "server"))]
+mod http;
+pub mod media;
+pub mod mirror;
+pub mod models;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod veekun;
 
 pub use error::Error;
 pub use error::Result;
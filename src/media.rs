@@ -0,0 +1,324 @@
+//! Downloading of sprites, artwork and cries referenced by the models.
+//!
+//! Files are kept in a content-addressed store: each file is stored once under the SHA-256
+//! of its content, e.g. `{root}/objects/3f/3fa1….png`, whatever url it was downloaded from.
+//! The store remembers which url gave which file in `{root}/index.json`, so stored urls are
+//! never requested again, like responses cached by `ApiClient`.
+//! Downloads go through the client, sharing its rate limit and retry policy.
+//!
+//! ```no_run
+//! # async fn example() -> pokemon_api::Result<()> {
+//! use pokemon_api::api::v2::client::ApiClient;
+//! use pokemon_api::api::v2::endpoint::ApiEndpoint;
+//! use pokemon_api::media::{HasMedia, MediaStore};
+//!
+//! let client = ApiClient::new()?;
+//! let pikachu = client.pokemon().get_by_name("pikachu").await?;
+//! let store = MediaStore::open("media")?;
+//! let paths = store.fetch_all(&client, pikachu.media_urls()).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::api::v2::client::ApiClient;
+use crate::mirror::write_atomic;
+use crate::models::v2::pokemon::{Pokemon, PokemonSprites};
+use crate::Result;
+
+/// Name of the file mapping urls to stored files.
+const INDEX_FILE: &str = "index.json";
+
+/// Directory of the stored files inside the root.
+const OBJECTS_DIR: &str = "objects";
+
+/// Number of downloads running at the same time by default.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Extensions of the media files found by `media_urls_in`.
+const MEDIA_EXTENSIONS: &[&str] = &["png", "gif", "svg", "jpg", "webp", "ogg", "mp3", "wav"];
+
+/// Model referencing media files.
+pub trait HasMedia {
+    /// Urls of the media files referenced by the model.
+    fn media_urls(&self) -> Vec<String>;
+}
+
+impl HasMedia for PokemonSprites {
+    fn media_urls(&self) -> Vec<String> {
+        vec![
+            &self.front_default,
+            &self.front_shiny,
+            &self.front_female,
+            &self.front_shiny_female,
+            &self.back_default,
+            &self.back_shiny,
+            &self.back_female,
+            &self.back_shiny_female,
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect()
+    }
+}
+
+impl HasMedia for Pokemon {
+    fn media_urls(&self) -> Vec<String> {
+        self.sprites.media_urls()
+    }
+}
+
+/// Urls of media files anywhere in the JSON document, e.g. the official artwork
+/// and cries of a Pokémon fetched with `get_raw_by_id`, which the models do not cover.
+pub fn media_urls_in(value: &Value) -> Vec<String> {
+    let mut urls = BTreeSet::new();
+    collect_media_urls(value, &mut urls);
+    urls.into_iter().collect()
+}
+
+fn collect_media_urls(value: &Value, urls: &mut BTreeSet<String>) {
+    match value {
+        Value::String(url) if is_media_url(url) => {
+            urls.insert(url.clone());
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_media_urls(item, urls)),
+        Value::Object(map) => map.values().for_each(|item| collect_media_urls(item, urls)),
+        _ => {}
+    }
+}
+
+fn is_media_url(url: &str) -> bool {
+    (url.starts_with("http://") || url.starts_with("https://"))
+        && extension(url).is_some_and(|ext| MEDIA_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Lowercase extension of the url path, e.g. `png`.
+fn extension(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next()?;
+    let name = path.rsplit('/').next()?;
+    let (_, ext) = name.rsplit_once('.')?;
+    if ext.is_empty() || ext.len() > 5 || !ext.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Some(ext.to_ascii_lowercase())
+}
+
+/// Local content-addressed store of downloaded media files.
+#[derive(Debug)]
+pub struct MediaStore {
+    root: PathBuf,
+    concurrency: usize,
+    /// Paths of the stored files relative to the root, by url.
+    index: Mutex<BTreeMap<String, String>>,
+}
+
+impl MediaStore {
+    /// Opens the store in the directory, creating it if needed.
+    pub fn open<P: Into<PathBuf>>(root: P) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+
+        let index_path = root.join(INDEX_FILE);
+        let index = if index_path.is_file() {
+            serde_json::from_str(&fs::read_to_string(index_path)?)?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            root,
+            concurrency: DEFAULT_CONCURRENCY,
+            index: Mutex::new(index),
+        })
+    }
+
+    /// Runs at most `concurrency` downloads at the same time.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Root directory of the store.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Local path of the file downloaded from the url, if stored.
+    pub fn path(&self, url: &str) -> Option<PathBuf> {
+        let index = self.index.lock().unwrap();
+        index.get(url).map(|path| self.root.join(path))
+    }
+
+    /// Number of distinct files in the store.
+    pub fn len(&self) -> usize {
+        let index = self.index.lock().unwrap();
+        index.values().collect::<BTreeSet<_>>().len()
+    }
+
+    /// Whether the store has no files.
+    pub fn is_empty(&self) -> bool {
+        self.index.lock().unwrap().is_empty()
+    }
+
+    /// Stores the content downloaded from the url and returns its local path.
+    /// Identical contents share the same file.
+    ///
+    /// The url is remembered in memory only, `flush` writes the index to disk.
+    pub fn put(&self, url: &str, content: &[u8]) -> Result<PathBuf> {
+        let hash = format!("{:x}", Sha256::digest(content));
+        let mut name = hash.clone();
+        if let Some(ext) = extension(url) {
+            name = format!("{}.{}", name, ext);
+        }
+        let relative = format!("{}/{}/{}", OBJECTS_DIR, &hash[..2], name);
+        let path = self.root.join(&relative);
+
+        if !path.is_file() {
+            fs::create_dir_all(path.parent().unwrap_or(&self.root))?;
+            write_atomic(&path, content)?;
+        }
+
+        self.index.lock().unwrap().insert(url.to_string(), relative);
+
+        Ok(path)
+    }
+
+    /// Writes the index mapping urls to stored files.
+    pub fn flush(&self) -> Result<()> {
+        let index = serde_json::to_vec_pretty(&*self.index.lock().unwrap())?;
+        write_atomic(&self.root.join(INDEX_FILE), &index)
+    }
+
+    /// Downloads the url unless already stored and returns the local path.
+    pub async fn fetch(&self, client: &ApiClient, url: &str) -> Result<PathBuf> {
+        let path = self.download(client, url).await?;
+        self.flush()?;
+        Ok(path)
+    }
+
+    /// Downloads every url not stored yet, each url once, and returns local paths by url.
+    /// The index is written once all downloads finish, also when some of them failed.
+    pub async fn fetch_all<I, S>(
+        &self,
+        client: &ApiClient,
+        urls: I,
+    ) -> Result<BTreeMap<String, PathBuf>>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let urls: BTreeSet<String> = urls.into_iter().map(Into::into).collect();
+
+        let paths = stream::iter(urls)
+            .map(|url| async move {
+                let path = self.download(client, &url).await?;
+                Ok((url, path))
+            })
+            .buffer_unordered(self.concurrency)
+            .try_collect()
+            .await;
+
+        self.flush()?;
+        paths
+    }
+
+    /// Downloads the url unless already stored, without writing the index.
+    async fn download(&self, client: &ApiClient, url: &str) -> Result<PathBuf> {
+        if let Some(path) = self.path(url) {
+            if path.is_file() {
+                return Ok(path);
+            }
+        }
+
+        let content = client.download(url).await?;
+        self.put(url, &content)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{media_urls_in, HasMedia, MediaStore};
+    use crate::api::v2::retry::RetryPolicy;
+    use crate::models::v2::pokemon::PokemonSprites;
+    use crate::testing::{Fault, MockServer};
+    use serde_json::json;
+    use std::env;
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    fn finds_media_urls() {
+        let sprites: PokemonSprites = serde_json::from_value(json!({
+            "front_default": "https://raw.githubusercontent.com/PokeAPI/sprites/master/sprites/pokemon/25.png",
+            "back_default": "https://raw.githubusercontent.com/PokeAPI/sprites/master/sprites/pokemon/back/25.png"
+        }))
+        .unwrap();
+        assert_eq!(sprites.media_urls().len(), 2);
+
+        let raw = json!({
+            "name": "pikachu",
+            "cries": { "latest": "https://raw.githubusercontent.com/PokeAPI/cries/main/cries/pokemon/latest/25.ogg" },
+            "sprites": { "other": { "official-artwork": { "front_default": "https://example.com/artwork/25.PNG" } } },
+            "species": { "name": "pikachu", "url": "https://pokeapi.co/api/v2/pokemon-species/25/" }
+        });
+        assert_eq!(
+            media_urls_in(&raw),
+            vec![
+                "https://example.com/artwork/25.PNG",
+                "https://raw.githubusercontent.com/PokeAPI/cries/main/cries/pokemon/latest/25.ogg",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stores_content_once() {
+        let server = MockServer::start().unwrap();
+        server.add_file("/sprites/25.png", b"pikachu".to_vec());
+        server.add_file("/sprites/25-copy.png", b"pikachu".to_vec());
+        server.add_file("/cries/25.ogg", b"pika".to_vec());
+        let client = server
+            .client()
+            .unwrap()
+            .with_retry(RetryPolicy::new(1, Duration::from_millis(10)));
+
+        let dir = env::temp_dir().join(format!("pokemon-api-media-{}", std::process::id()));
+        let store = MediaStore::open(&dir).unwrap().with_concurrency(2);
+        let urls = vec![
+            server.url("/sprites/25.png"),
+            server.url("/sprites/25-copy.png"),
+            server.url("/cries/25.ogg"),
+            server.url("/sprites/25.png"),
+        ];
+
+        server.add_fault_times(Fault::Status(503), 1);
+        let paths = store.fetch_all(&client, urls.clone()).await.unwrap();
+        assert_eq!(paths.len(), 3);
+        assert_eq!(paths[&urls[0]], paths[&urls[1]]);
+        assert_eq!(fs::read(&paths[&urls[2]]).unwrap(), b"pika");
+        assert!(paths[&urls[2]].to_string_lossy().ends_with(".ogg"));
+        assert_eq!(store.len(), 2);
+        assert_eq!(server.requests().len(), 4);
+
+        // Stored urls are not requested again, also after reopening the store.
+        let store = MediaStore::open(&dir).unwrap();
+        store.fetch_all(&client, urls.clone()).await.unwrap();
+        assert_eq!(store.path(&urls[1]), Some(paths[&urls[1]].clone()));
+        assert_eq!(server.requests().len(), 4);
+
+        assert!(store
+            .fetch(&client, &server.url("/sprites/missing.png"))
+            .await
+            .is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,312 @@
+//! Mirror of the whole API on the local disk.
+//!
+//! Resources are stored using the layout of the [PokeAPI/api-data](https://github.com/PokeAPI/api-data)
+//! repository, e.g. `{root}/api/v2/berry/index.json` for the resource list and
+//! `{root}/api/v2/berry/1/index.json` for the resource itself.
+//! All urls inside the stored documents are rewritten to point at the configured host.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::api::v2::client::ApiClient;
+use crate::api::v2::endpoint::{ApiEndpoint, MAX_PAGINATION_LIMIT};
+use crate::api::v2::resource::ApiNamedResourceList;
+use crate::models::v2::resource::{url_id, NamedResource, NamedResourceList};
+use crate::Result;
+
+/// Name of the file storing a resource inside its directory.
+const INDEX_FILE: &str = "index.json";
+
+/// Callback receiving mirroring progress.
+type ProgressCallback = Box<dyn Fn(&Progress) + Send + Sync>;
+
+/// Progress of mirroring a single endpoint.
+#[derive(Clone, Debug)]
+pub struct Progress {
+    /// Name of the endpoint being mirrored.
+    pub endpoint: &'static str,
+
+    /// Number of resources processed so far, including the current one.
+    pub processed: usize,
+
+    /// Total number of resources of the endpoint.
+    pub total: usize,
+
+    /// Url of the current resource.
+    pub url: String,
+
+    /// Whether the resource was already on disk and has not been downloaded.
+    pub skipped: bool,
+}
+
+/// Statistics of a mirroring run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MirrorStats {
+    /// Number of downloaded resources.
+    pub fetched: usize,
+
+    /// Number of resources found on disk.
+    pub skipped: usize,
+}
+
+impl MirrorStats {
+    fn merge(&mut self, other: MirrorStats) {
+        self.fetched += other.fetched;
+        self.skipped += other.skipped;
+    }
+}
+
+/// Dumps API resources to the local directory.
+/// Mirroring is resumable: resources already present on disk are not requested again.
+/// Requests go through the given client, so its rate limit is respected.
+pub struct Mirror {
+    client: ApiClient,
+    root: PathBuf,
+    host: String,
+    progress: Option<ProgressCallback>,
+}
+
+impl Mirror {
+    /// Creates mirror writing into `root` directory.
+    /// By default urls are rewritten to host relative ones, e.g. `/api/v2/berry/1/`.
+    pub fn new<P: Into<PathBuf>>(client: ApiClient, root: P) -> Self {
+        Self {
+            client,
+            root: root.into(),
+            host: String::new(),
+            progress: None,
+        }
+    }
+
+    /// Sets the host urls are rewritten to, e.g. `http://localhost:8080`.
+    pub fn with_host<T: Into<String>>(mut self, host: T) -> Self {
+        self.host = host.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets callback invoked after each processed resource.
+    pub fn on_progress<F: Fn(&Progress) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Root directory of the mirror.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Mirrors every endpoint known to the client.
+    pub async fn run(&self) -> Result<MirrorStats> {
+        let mut stats = MirrorStats::default();
+
+        stats.merge(self.mirror_endpoint(&self.client.berries()).await?);
+        stats.merge(
+            self.mirror_endpoint(&self.client.berry_firmnesses())
+                .await?,
+        );
+        stats.merge(self.mirror_endpoint(&self.client.types()).await?);
+        stats.merge(self.mirror_endpoint(&self.client.pokemon()).await?);
+        stats.merge(self.mirror_endpoint(&self.client.natures()).await?);
+        stats.merge(self.mirror_endpoint(&self.client.pokemon_species()).await?);
+        stats.merge(self.mirror_endpoint(&self.client.egg_groups()).await?);
+        stats.merge(self.mirror_endpoint(&self.client.moves()).await?);
+
+        Ok(stats)
+    }
+
+    /// Mirrors all resources of a single endpoint.
+    pub async fn mirror_endpoint<E: ApiEndpoint + Sync>(
+        &self,
+        endpoint: &E,
+    ) -> Result<MirrorStats> {
+        let list = endpoint.all_paginated(0, MAX_PAGINATION_LIMIT).await?;
+        let resources = list.resources();
+        let mut stats = MirrorStats::default();
+
+        let index = NamedResourceList {
+            count: resources.len(),
+            next: None,
+            previous: None,
+            results: resources
+                .iter()
+                .map(|res| NamedResource {
+                    name: res.name(),
+                    url: res.url(),
+                })
+                .collect(),
+        };
+        self.store(&[E::name()], serde_json::to_value(index)?)?;
+
+        for (idx, resource) in resources.iter().enumerate() {
+            let url = resource.url();
+            let id = url_id(&url)
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| resource.name());
+            let skipped = self.exists(&[E::name(), id.as_str()]);
+
+            if skipped {
+                stats.skipped += 1;
+            } else {
+                let value = self.client.request_object::<Value, _>(url.as_str()).await?;
+                self.store(&[E::name(), id.as_str()], value)?;
+                stats.fetched += 1;
+            }
+
+            if let Some(ref callback) = self.progress {
+                callback(&Progress {
+                    endpoint: E::name(),
+                    processed: idx + 1,
+                    total: resources.len(),
+                    url,
+                    skipped,
+                });
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Directory of the resource given its path segments after `api/v2`.
+    fn path(&self, segments: &[&str]) -> PathBuf {
+        segments
+            .iter()
+            .fold(self.root.join("api").join("v2"), |path, seg| path.join(seg))
+    }
+
+    fn exists(&self, segments: &[&str]) -> bool {
+        self.path(segments).join(INDEX_FILE).is_file()
+    }
+
+    /// Rewrites urls of the document and writes it to disk.
+    fn store(&self, segments: &[&str], mut value: Value) -> Result<()> {
+        let target = format!("{}/api/v2", self.host);
+        rewrite_urls(&mut value, self.client.api_url(), &target);
+
+        let dir = self.path(segments);
+        fs::create_dir_all(&dir)?;
+
+        write_atomic(&dir.join(INDEX_FILE), &serde_json::to_vec_pretty(&value)?)
+    }
+}
+
+/// Writes the file under a temporary name first, so interrupted runs never leave broken files.
+/// Temporary names are unique, so concurrent writers of the same file do not collide.
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    static NEXT_TMP: AtomicUsize = AtomicUsize::new(0);
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(
+        ".{}-{}.tmp",
+        std::process::id(),
+        NEXT_TMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Reads every resource of the directory written by `Mirror`, with the endpoint of each,
+/// e.g. `{dir}/api/v2/berry/1/index.json`. Resource lists are skipped.
+#[cfg(any(test, feature = "testing", feature = "server"))]
+pub(crate) fn read_snapshot<P: AsRef<Path>>(dir: P) -> Result<Vec<(String, Value)>> {
+    let mut resources = Vec::new();
+
+    for endpoint in fs::read_dir(dir.as_ref().join("api").join("v2"))? {
+        let endpoint = endpoint?;
+        if !endpoint.file_type()?.is_dir() {
+            continue;
+        }
+
+        let name = endpoint.file_name().to_string_lossy().to_string();
+        for resource in fs::read_dir(endpoint.path())? {
+            let path = resource?.path().join(INDEX_FILE);
+            if path.is_file() {
+                let value = serde_json::from_str(&fs::read_to_string(path)?)?;
+                resources.push((name.clone(), value));
+            }
+        }
+    }
+
+    Ok(resources)
+}
+
+/// Loads every resource of the endpoint stored in the directory by `Mirror`, ordered by id.
+pub(crate) fn load_all<M: DeserializeOwned>(dir: &Path, endpoint: &str) -> Result<Vec<M>> {
+    let mut entries = Vec::new();
+
+    for entry in fs::read_dir(dir.join("api").join("v2").join(endpoint))? {
+        let path = entry?.path();
+        let id = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.parse::<usize>().ok());
+        let path = path.join(INDEX_FILE);
+        if let (Some(id), true) = (id, path.is_file()) {
+            entries.push((id, path));
+        }
+    }
+
+    entries.sort();
+    entries
+        .into_iter()
+        .map(|(_, path)| Ok(serde_json::from_str(&fs::read_to_string(path)?)?))
+        .collect()
+}
+
+/// Replaces prefix `from` with `to` in every string of the document.
+pub(crate) fn rewrite_urls(value: &mut Value, from: &str, to: &str) {
+    match value {
+        Value::String(s) if s.starts_with(from) => {
+            *s = format!("{}{}", to, &s[from.len()..]);
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| rewrite_urls(value, from, to)),
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|value| rewrite_urls(value, from, to)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::rewrite_urls;
+    use serde_json::json;
+
+    #[test]
+    fn rewrites_nested_urls() {
+        let mut value = json!({
+            "name": "cheri",
+            "firmness": { "name": "soft", "url": "https://pokeapi.co/api/v2/berry-firmness/2/" },
+            "flavors": [
+                { "potency": 10, "flavor": { "name": "spicy", "url": "https://pokeapi.co/api/v2/berry-flavor/1/" } }
+            ],
+            "sprite": "https://raw.githubusercontent.com/PokeAPI/sprites/master/items/cheri-berry.png"
+        });
+
+        rewrite_urls(
+            &mut value,
+            "https://pokeapi.co/api/v2",
+            "http://localhost:8080/api/v2",
+        );
+
+        assert_eq!(
+            value["firmness"]["url"],
+            "http://localhost:8080/api/v2/berry-firmness/2/"
+        );
+        assert_eq!(
+            value["flavors"][0]["flavor"]["url"],
+            "http://localhost:8080/api/v2/berry-flavor/1/"
+        );
+        assert_eq!(
+            value["sprite"],
+            "https://raw.githubusercontent.com/PokeAPI/sprites/master/items/cheri-berry.png"
+        );
+        assert_eq!(value["name"], "cheri");
+    }
+}
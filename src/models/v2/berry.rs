@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::common::{Id, Integer, Name};
+use super::common::{HasNames, Id, Integer, Name};
 use super::resource::NamedResource;
 
 /// Berries are small fruits that can provide HP and status condition restoration,
@@ -76,6 +76,12 @@ pub struct BerryFirmness {
     pub names: Vec<Name>,
 }
 
+impl HasNames for BerryFirmness {
+    fn names(&self) -> &[Name] {
+        &self.names
+    }
+}
+
 /// Flavors determine whether a Pokémon will benefit or suffer from eating a berry
 /// based on their [nature](https://pokeapi.co/docs/v2.html/#natures).
 /// Check out [Bulbapedia](http://bulbapedia.bulbagarden.net/wiki/Flavor) for greater detail.
@@ -97,6 +103,12 @@ pub struct BerryFlavor {
     pub names: Vec<Name>,
 }
 
+impl HasNames for BerryFlavor {
+    fn names(&self) -> &[Name] {
+        &self.names
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FlavorBerryMap {
     /// How powerful the referenced flavor is for this berry.
@@ -12,3 +12,284 @@ pub struct Name {
     /// The language this name is in.
     pub language: NamedResource,
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Description {
+    /// The localized description for an API resource in a specific language.
+    pub description: String,
+
+    /// The language this description is in.
+    pub language: NamedResource,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Effect {
+    /// The localized effect text for an API resource in a specific language.
+    pub effect: String,
+
+    /// The language this effect is in.
+    pub language: NamedResource,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerboseEffect {
+    /// The localized effect text for an API resource in a specific language.
+    pub effect: String,
+
+    /// The localized effect text in brief.
+    pub short_effect: String,
+
+    /// The language this effect is in.
+    pub language: NamedResource,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FlavorText {
+    /// The localized flavor text for an API resource in a specific language.
+    pub flavor_text: String,
+
+    /// The language this flavor text is in.
+    pub language: NamedResource,
+
+    /// The game version this flavor text is extracted from.
+    pub version: Option<NamedResource>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GenerationGameIndex {
+    /// The internal id of an API resource within game data.
+    pub game_index: Integer,
+
+    /// The generation relevant to this game index.
+    pub generation: NamedResource,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VersionGameIndex {
+    /// The internal id of an API resource within game data.
+    pub game_index: Integer,
+
+    /// The version relevant to this game index.
+    pub version: NamedResource,
+}
+
+/// Entry written in a specific language.
+pub trait Localized {
+    /// Name of the language, e.g. `en` or `ja-Hrkt`.
+    fn language(&self) -> &str;
+}
+
+impl Localized for Name {
+    fn language(&self) -> &str {
+        &self.language.name
+    }
+}
+
+impl Localized for Description {
+    fn language(&self) -> &str {
+        &self.language.name
+    }
+}
+
+impl Localized for Effect {
+    fn language(&self) -> &str {
+        &self.language.name
+    }
+}
+
+impl Localized for VerboseEffect {
+    fn language(&self) -> &str {
+        &self.language.name
+    }
+}
+
+impl Localized for FlavorText {
+    fn language(&self) -> &str {
+        &self.language.name
+    }
+}
+
+/// Localized entry carrying a text, like a flavor text or an effect.
+pub trait LocalizedText: Localized {
+    /// The text in the language of the entry.
+    fn text(&self) -> &str;
+}
+
+impl LocalizedText for Description {
+    fn text(&self) -> &str {
+        &self.description
+    }
+}
+
+impl LocalizedText for Effect {
+    fn text(&self) -> &str {
+        &self.effect
+    }
+}
+
+impl LocalizedText for VerboseEffect {
+    fn text(&self) -> &str {
+        &self.effect
+    }
+}
+
+impl LocalizedText for FlavorText {
+    fn text(&self) -> &str {
+        &self.flavor_text
+    }
+}
+
+/// Selects the entry in the most preferred language available.
+///
+/// Languages are tried in order, so `&["de", "en"]` falls back to English when there is no
+/// German entry. A language matches entries in the same language ignoring case, or when
+/// there are none, entries of its variants: `zh` matches `zh-Hant`, `de-AT` matches `de`.
+pub fn select_language<'a, T: Localized>(entries: &'a [T], languages: &[&str]) -> Option<&'a T> {
+    languages.iter().find_map(|language| {
+        entries
+            .iter()
+            .find(|entry| entry.language().eq_ignore_ascii_case(language))
+            .or_else(|| {
+                entries
+                    .iter()
+                    .find(|entry| primary_subtag(entry.language()) == primary_subtag(language))
+            })
+    })
+}
+
+/// Primary subtag of the language, e.g. `zh` for `zh-Hant`.
+fn primary_subtag(language: &str) -> String {
+    language
+        .split('-')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Model carrying the name of the resource listed in different languages.
+pub trait HasNames {
+    /// The name of this resource listed in different languages.
+    fn names(&self) -> &[Name];
+
+    /// The name in the most preferred language available, see `select_language`.
+    fn localized_name(&self, languages: &[&str]) -> Option<&str> {
+        select_language(self.names(), languages).map(|name| name.name.as_str())
+    }
+}
+
+/// Model carrying the description of the resource listed in different languages.
+pub trait HasDescriptions {
+    /// The description of this resource listed in different languages.
+    fn descriptions(&self) -> &[Description];
+
+    /// The description in the most preferred language available, see `select_language`.
+    fn localized_description(&self, languages: &[&str]) -> Option<&str> {
+        select_language(self.descriptions(), languages).map(|d| d.description.as_str())
+    }
+}
+
+/// Model carrying the flavor text of the resource listed in different languages.
+pub trait HasFlavorTexts {
+    /// Flavor text entry of the model, e.g. `FlavorText` or `MoveFlavorText`.
+    type FlavorText: LocalizedText;
+
+    /// The flavor text of this resource listed in different languages.
+    fn flavor_text_entries(&self) -> &[Self::FlavorText];
+
+    /// The flavor text in the most preferred language available, see `select_language`.
+    fn localized_flavor_text(&self, languages: &[&str]) -> Option<&str> {
+        select_language(self.flavor_text_entries(), languages).map(LocalizedText::text)
+    }
+}
+
+/// Model carrying the effect of the resource listed in different languages.
+pub trait HasEffectEntries {
+    /// Effect entry of the model, `Effect` or `VerboseEffect`.
+    type Effect: LocalizedText;
+
+    /// The effect of this resource listed in different languages.
+    fn effect_entries(&self) -> &[Self::Effect];
+
+    /// The effect in the most preferred language available, see `select_language`.
+    fn localized_effect(&self, languages: &[&str]) -> Option<&Self::Effect> {
+        select_language(self.effect_entries(), languages)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{select_language, Effect, HasNames, LocalizedText, Name};
+    use crate::models::v2::berry::BerryFirmness;
+    use crate::models::v2::moves::MoveFlavorText;
+    use crate::models::v2::resource::NamedResource;
+
+    fn name(name: &str, language: &str) -> Name {
+        Name {
+            name: name.into(),
+            language: NamedResource {
+                name: language.into(),
+                url: String::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn falls_back_through_languages() {
+        let firmness = BerryFirmness {
+            id: 5,
+            name: "super-hard".into(),
+            berries: vec![],
+            names: vec![
+                name("Super dure", "fr"),
+                name("Steinhart", "de"),
+                name("Super Hard", "en"),
+                name("超硬", "zh-Hant"),
+            ],
+        };
+
+        assert_eq!(firmness.localized_name(&["fr", "en"]), Some("Super dure"));
+        assert_eq!(firmness.localized_name(&["ko", "en"]), Some("Super Hard"));
+        assert_eq!(firmness.localized_name(&["de-AT", "en"]), Some("Steinhart"));
+        assert_eq!(firmness.localized_name(&["zh"]), Some("超硬"));
+        assert_eq!(firmness.localized_name(&["EN"]), Some("Super Hard"));
+        assert_eq!(firmness.localized_name(&["ja"]), None);
+        assert_eq!(firmness.localized_name(&[]), None);
+    }
+
+    #[test]
+    fn prefers_exact_language() {
+        let names = vec![name("中文", "zh-Hans"), name("中文繁體", "zh-Hant")];
+
+        let selected = select_language(&names, &["zh-Hant"]).unwrap();
+        assert_eq!(selected.name, "中文繁體");
+    }
+
+    #[test]
+    fn selects_localized_texts() {
+        let language = |name: &str| NamedResource {
+            name: name.into(),
+            url: String::new(),
+        };
+        let flavor_texts = vec![
+            MoveFlavorText {
+                flavor_text: "Pound the foe.".into(),
+                language: language("en"),
+                version_group: language("red-blue"),
+            },
+            MoveFlavorText {
+                flavor_text: "Écrase l'ennemi.".into(),
+                language: language("fr"),
+                version_group: language("red-blue"),
+            },
+        ];
+        let effects = vec![Effect {
+            effect: "Inflicts regular damage.".into(),
+            language: language("en"),
+        }];
+
+        let flavor_text = select_language(&flavor_texts, &["fr-CA", "en"]).unwrap();
+        assert_eq!(flavor_text.text(), "Écrase l'ennemi.");
+        let effect = select_language(&effects, &["de", "en"]).unwrap();
+        assert_eq!(effect.text(), "Inflicts regular damage.");
+    }
+}
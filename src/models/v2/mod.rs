@@ -1,3 +1,5 @@
 pub mod berry;
 pub mod common;
+pub mod moves;
+pub mod pokemon;
 pub mod resource;
@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+
+use super::common::{
+    HasEffectEntries, HasFlavorTexts, HasNames, Id, Integer, Localized, LocalizedText, Name,
+    VerboseEffect,
+};
+use super::resource::NamedResource;
+
+/// Moves are the skills of Pokémon in battle. In battle, a Pokémon uses one move each turn.
+/// Some moves (including those learned by Hidden Machine) can be used outside of battle as well,
+/// usually for the purpose of removing obstacles or exploring new areas.
+/// Check out [Bulbapedia](http://bulbapedia.bulbagarden.net/wiki/Move) for greater detail.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Move {
+    /// The identifier for this resource.
+    pub id: Id,
+
+    /// The name for this resource.
+    pub name: String,
+
+    /// The percent value of how likely this move is to be successful.
+    pub accuracy: Option<Integer>,
+
+    /// The percent value of how likely it is this moves effect will happen.
+    pub effect_chance: Option<Integer>,
+
+    /// Power points. The number of times this move can be used.
+    pub pp: Option<Integer>,
+
+    /// A value between -8 and 8. Sets the order in which moves are executed during battle.
+    pub priority: Integer,
+
+    /// The base power of this move with a value of 0 if it does not have a base power.
+    pub power: Option<Integer>,
+
+    /// The type of damage the move inflicts on the target, e.g. physical.
+    pub damage_class: NamedResource,
+
+    /// The effect of this move listed in different languages.
+    pub effect_entries: Vec<VerboseEffect>,
+
+    /// The flavor text of this move listed in different languages.
+    #[serde(default)]
+    pub flavor_text_entries: Vec<MoveFlavorText>,
+
+    /// The generation in which this move was introduced.
+    pub generation: NamedResource,
+
+    /// Metadata about this move.
+    pub meta: Option<MoveMetaData>,
+
+    /// The name of this resource listed in different languages.
+    pub names: Vec<Name>,
+
+    /// A list of move resource value changes across version groups of the game.
+    #[serde(default)]
+    pub past_values: Vec<PastMoveStatValues>,
+
+    /// A list of stats this moves effects and how much it effects them.
+    #[serde(default)]
+    pub stat_changes: Vec<MoveStatChange>,
+
+    /// The type of target that will receive the effects of the attack.
+    pub target: NamedResource,
+
+    /// The elemental type of this move.
+    pub r#type: NamedResource,
+}
+
+impl HasNames for Move {
+    fn names(&self) -> &[Name] {
+        &self.names
+    }
+}
+
+impl HasEffectEntries for Move {
+    type Effect = VerboseEffect;
+
+    fn effect_entries(&self) -> &[VerboseEffect] {
+        &self.effect_entries
+    }
+}
+
+impl HasFlavorTexts for Move {
+    type FlavorText = MoveFlavorText;
+
+    fn flavor_text_entries(&self) -> &[MoveFlavorText] {
+        &self.flavor_text_entries
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MoveFlavorText {
+    /// The localized flavor text for an api resource in a specific language.
+    pub flavor_text: String,
+
+    /// The language this name is in.
+    pub language: NamedResource,
+
+    /// The version group that uses this flavor text.
+    pub version_group: NamedResource,
+}
+
+impl Localized for MoveFlavorText {
+    fn language(&self) -> &str {
+        &self.language.name
+    }
+}
+
+impl LocalizedText for MoveFlavorText {
+    fn text(&self) -> &str {
+        &self.flavor_text
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MoveMetaData {
+    /// The status ailment this move inflicts on its target.
+    pub ailment: NamedResource,
+
+    /// The category of move this move falls under, e.g. damage or ailment.
+    pub category: NamedResource,
+
+    /// The minimum number of times this move hits. Null if it always only hits once.
+    pub min_hits: Option<Integer>,
+
+    /// The maximum number of times this move hits. Null if it always only hits once.
+    pub max_hits: Option<Integer>,
+
+    /// The minimum number of turns this move continues to take effect.
+    /// Null if it always only lasts one turn.
+    pub min_turns: Option<Integer>,
+
+    /// The maximum number of turns this move continues to take effect.
+    /// Null if it always only lasts one turn.
+    pub max_turns: Option<Integer>,
+
+    /// HP drain (if positive) or Recoil damage (if negative), in percent of damage done.
+    pub drain: Integer,
+
+    /// The amount of hp gained by the attacking Pokémon, in percent of it's maximum HP.
+    pub healing: Integer,
+
+    /// Critical hit rate bonus.
+    pub crit_rate: Integer,
+
+    /// The likelihood this attack will cause an ailment.
+    pub ailment_chance: Integer,
+
+    /// The likelihood this attack will cause the target Pokémon to flinch.
+    pub flinch_chance: Integer,
+
+    /// The likelihood this attack will cause a stat change in the target Pokémon.
+    pub stat_chance: Integer,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MoveStatChange {
+    /// The amount of change.
+    pub change: Integer,
+
+    /// The stat being affected.
+    pub stat: NamedResource,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PastMoveStatValues {
+    /// The percent value of how likely this move is to be successful.
+    pub accuracy: Option<Integer>,
+
+    /// The percent value of how likely it is this moves effect will take effect.
+    pub effect_chance: Option<Integer>,
+
+    /// The base power of this move with a value of 0 if it does not have a base power.
+    pub power: Option<Integer>,
+
+    /// Power points. The number of times this move can be used.
+    pub pp: Option<Integer>,
+
+    /// The effect of this move listed in different languages.
+    #[serde(default)]
+    pub effect_entries: Vec<VerboseEffect>,
+
+    /// The elemental type of this move.
+    pub r#type: Option<NamedResource>,
+
+    /// The version group in which these move stat values were in effect.
+    pub version_group: NamedResource,
+}
@@ -0,0 +1,780 @@
+use serde::{Deserialize, Serialize};
+
+use super::common::{
+    Description, FlavorText, GenerationGameIndex, HasDescriptions, HasFlavorTexts, HasNames, Id,
+    Integer, Name, VersionGameIndex,
+};
+use super::resource::{NamedResource, Resource};
+
+/// Types are properties for Pokémon and their moves.
+/// Each type has three properties: which types of Pokémon it is super effective against,
+/// which types of Pokémon it is not very effective against,
+/// and which types of Pokémon it is completely ineffective against.
+/// Check out [Bulbapedia](http://bulbapedia.bulbagarden.net/wiki/Type) for greater detail.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Type {
+    /// The identifier for this resource.
+    pub id: Id,
+
+    /// The name for this resource.
+    pub name: String,
+
+    /// A detail of how effective this type is toward others and vice versa.
+    pub damage_relations: TypeRelations,
+
+    /// A list of details of how effective this type was toward others and vice versa
+    /// in previous generations.
+    pub past_damage_relations: Vec<TypeRelationsPast>,
+
+    /// A list of game indices relevant to this item by generation.
+    pub game_indices: Vec<GenerationGameIndex>,
+
+    /// The generation this type was introduced in.
+    pub generation: NamedResource,
+
+    /// The class of damage inflicted by this type.
+    pub move_damage_class: Option<NamedResource>,
+
+    /// The name of this resource listed in different languages.
+    pub names: Vec<Name>,
+
+    /// A list of details of Pokémon that have this type.
+    pub pokemon: Vec<TypePokemon>,
+
+    /// A list of moves that have this type.
+    pub moves: Vec<NamedResource>,
+}
+
+impl HasNames for Type {
+    fn names(&self) -> &[Name] {
+        &self.names
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TypePokemon {
+    /// The order the Pokémon's types are listed in.
+    pub slot: Integer,
+
+    /// The Pokémon that has the referenced type.
+    pub pokemon: NamedResource,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TypeRelations {
+    /// A list of types this type has no effect on.
+    pub no_damage_to: Vec<NamedResource>,
+
+    /// A list of types this type is not very effective against.
+    pub half_damage_to: Vec<NamedResource>,
+
+    /// A list of types this type is very effective against.
+    pub double_damage_to: Vec<NamedResource>,
+
+    /// A list of types that have no effect on this type.
+    pub no_damage_from: Vec<NamedResource>,
+
+    /// A list of types that are not very effective against this type.
+    pub half_damage_from: Vec<NamedResource>,
+
+    /// A list of types that are very effective against this type.
+    pub double_damage_from: Vec<NamedResource>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TypeRelationsPast {
+    /// The last generation in which the referenced type had the listed damage relations.
+    pub generation: NamedResource,
+
+    /// The damage relations the referenced type had up to and including the listed generation.
+    pub damage_relations: TypeRelations,
+}
+
+/// Natures influence how a Pokémon's stats grow.
+/// See [Bulbapedia](http://bulbapedia.bulbagarden.net/wiki/Nature) for greater detail.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Nature {
+    /// The identifier for this resource.
+    pub id: Id,
+
+    /// The name for this resource.
+    pub name: String,
+
+    /// The stat decreased by 10% in Pokémon with this nature.
+    pub decreased_stat: Option<NamedResource>,
+
+    /// The stat increased by 10% in Pokémon with this nature.
+    pub increased_stat: Option<NamedResource>,
+
+    /// The flavor hated by Pokémon with this nature.
+    pub hates_flavor: Option<NamedResource>,
+
+    /// The flavor liked by Pokémon with this nature.
+    pub likes_flavor: Option<NamedResource>,
+
+    /// A list of Pokéathlon stats this nature effects and how much it effects them.
+    pub pokeathlon_stat_changes: Vec<NatureStatChange>,
+
+    /// A list of battle styles and how likely a Pokémon with this nature
+    /// is to use them in the Battle Palace or Battle Tent.
+    pub move_battle_style_preferences: Vec<MoveBattleStylePreference>,
+
+    /// The name of this resource listed in different languages.
+    pub names: Vec<Name>,
+}
+
+impl HasNames for Nature {
+    fn names(&self) -> &[Name] {
+        &self.names
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NatureStatChange {
+    /// The amount of change.
+    pub max_change: Integer,
+
+    /// The stat being affected.
+    pub pokeathlon_stat: NamedResource,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MoveBattleStylePreference {
+    /// Chance of using the move, in percent, if HP is under one half.
+    pub low_hp_preference: Integer,
+
+    /// Chance of using the move, in percent, if HP is over one half.
+    pub high_hp_preference: Integer,
+
+    /// The move battle style.
+    pub move_battle_style: NamedResource,
+}
+
+/// Pokémon are the creatures that inhabit the world of the Pokémon games.
+/// They can be caught using Pokéballs and trained by battling with other Pokémon.
+/// Each Pokémon belongs to a specific species but may take on a variant which makes it differ
+/// from other Pokémon of the same species, such as base stats, available abilities and typings.
+/// See [Bulbapedia](http://bulbapedia.bulbagarden.net/wiki/Pok%C3%A9mon_(species)) for greater detail.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Pokemon {
+    /// The identifier for this resource.
+    pub id: Id,
+
+    /// The name for this resource.
+    pub name: String,
+
+    /// The base experience gained for defeating this Pokémon.
+    pub base_experience: Option<Integer>,
+
+    /// The height of this Pokémon in decimetres.
+    pub height: Integer,
+
+    /// Set for exactly one Pokémon used as the default for each species.
+    pub is_default: bool,
+
+    /// Order for sorting. Almost national order, except families are grouped together.
+    pub order: Integer,
+
+    /// The weight of this Pokémon in hectograms.
+    pub weight: Integer,
+
+    /// A list of abilities this Pokémon could potentially have.
+    pub abilities: Vec<PokemonAbility>,
+
+    /// A list of forms this Pokémon can take on.
+    pub forms: Vec<NamedResource>,
+
+    /// A list of game indices relevant to Pokémon item by generation.
+    pub game_indices: Vec<VersionGameIndex>,
+
+    /// A list of items this Pokémon may be holding when encountered.
+    pub held_items: Vec<PokemonHeldItem>,
+
+    /// A link to a list of location areas, as well as encounter details pertaining to specific versions.
+    pub location_area_encounters: String,
+
+    /// A list of moves along with learn methods and level details pertaining to specific version groups.
+    pub moves: Vec<PokemonMove>,
+
+    /// A list of details showing types this Pokémon had in previous generations.
+    #[serde(default)]
+    pub past_types: Vec<PokemonTypePast>,
+
+    /// A set of sprites used to depict this Pokémon in the game.
+    pub sprites: PokemonSprites,
+
+    /// The species this Pokémon belongs to.
+    pub species: NamedResource,
+
+    /// A list of base stat values for this Pokémon.
+    pub stats: Vec<PokemonStat>,
+
+    /// A list of details showing types this Pokémon has.
+    pub types: Vec<PokemonType>,
+}
+
+impl Pokemon {
+    /// Every move the Pokémon learns in the version group, ordered by method, level and name.
+    pub fn learnset(&self, version_group: &str) -> Vec<LearnedMove> {
+        let mut learnset: Vec<LearnedMove> = self
+            .moves
+            .iter()
+            .flat_map(|m| {
+                m.version_group_details
+                    .iter()
+                    .filter(move |details| details.version_group.name == version_group)
+                    .map(move |details| LearnedMove {
+                        name: m.r#move.name.clone(),
+                        method: LearnMethod::from_name(&details.move_learn_method.name),
+                        level: details.level_learned_at,
+                    })
+            })
+            .collect();
+
+        learnset.sort_by(|a, b| (&a.method, a.level, &a.name).cmp(&(&b.method, b.level, &b.name)));
+        learnset.dedup();
+        learnset
+    }
+
+    /// Moves learned by leveling up in the version group up to and including the level.
+    pub fn level_up_moves(&self, version_group: &str, max_level: Integer) -> Vec<LearnedMove> {
+        let mut moves: Vec<LearnedMove> = self
+            .learned_by(version_group, &LearnMethod::LevelUp)
+            .into_iter()
+            .filter(|m| m.level <= max_level)
+            .collect();
+
+        moves.sort_by(|a, b| a.level.cmp(&b.level).then_with(|| a.name.cmp(&b.name)));
+        moves
+    }
+
+    /// Moves taught by TMs, HMs and TRs in the version group.
+    pub fn machine_moves(&self, version_group: &str) -> Vec<LearnedMove> {
+        self.learned_by(version_group, &LearnMethod::Machine)
+    }
+
+    /// Moves inherited from the parents when hatching in the version group.
+    pub fn egg_moves(&self, version_group: &str) -> Vec<LearnedMove> {
+        self.learned_by(version_group, &LearnMethod::Egg)
+    }
+
+    /// Moves taught by move tutors in the version group.
+    pub fn tutor_moves(&self, version_group: &str) -> Vec<LearnedMove> {
+        self.learned_by(version_group, &LearnMethod::Tutor)
+    }
+
+    /// Whether the Pokémon learns the move in the version group in any way.
+    pub fn learns(&self, version_group: &str, name: &str) -> bool {
+        self.learnset(version_group).iter().any(|m| m.name == name)
+    }
+
+    /// Changes of the learnset from one version group to another.
+    /// Moves are matched by name, method and level first. A level-up move left over on both
+    /// sides, i.e. learned at another level, is reported as changed rather than removed
+    /// and added, pairing the lowest levels first when the move is learned at several levels.
+    pub fn learnset_diff(&self, from: &str, to: &str) -> LearnsetDiff {
+        let before = self.learnset(from);
+        let after = self.learnset(to);
+
+        let mut removed: Vec<LearnedMove> = before
+            .iter()
+            .filter(|m| !after.contains(m))
+            .cloned()
+            .collect();
+
+        let mut diff = LearnsetDiff::default();
+        for m in after.iter().filter(|m| !before.contains(m)) {
+            let old = removed
+                .iter()
+                .position(|old| old.name == m.name && old.method == m.method);
+            match old {
+                Some(old) => {
+                    let old = removed.remove(old);
+                    diff.changed.push(LevelChange {
+                        name: m.name.clone(),
+                        from: old.level,
+                        to: m.level,
+                    })
+                }
+                None => diff.added.push(m.clone()),
+            }
+        }
+        diff.removed = removed;
+
+        diff
+    }
+
+    fn learned_by(&self, version_group: &str, method: &LearnMethod) -> Vec<LearnedMove> {
+        self.learnset(version_group)
+            .into_iter()
+            .filter(|m| m.method == *method)
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PokemonAbility {
+    /// Whether or not this is a hidden ability.
+    pub is_hidden: bool,
+
+    /// The slot this ability occupies in this Pokémon species.
+    pub slot: Integer,
+
+    /// The ability the Pokémon may have.
+    pub ability: NamedResource,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PokemonType {
+    /// The order the Pokémon's types are listed in.
+    pub slot: Integer,
+
+    /// The type the referenced Pokémon has.
+    pub r#type: NamedResource,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PokemonTypePast {
+    /// The last generation in which the referenced Pokémon had the listed types.
+    pub generation: NamedResource,
+
+    /// The types the referenced Pokémon had up to and including the listed generation.
+    pub types: Vec<PokemonType>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PokemonHeldItem {
+    /// The item the referenced Pokémon holds.
+    pub item: NamedResource,
+
+    /// The details of the different versions in which the item is held.
+    pub version_details: Vec<PokemonHeldItemVersion>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PokemonHeldItemVersion {
+    /// The version in which the item is held.
+    pub version: NamedResource,
+
+    /// How often the item is held.
+    pub rarity: Integer,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PokemonMove {
+    /// The move the Pokémon can learn.
+    pub r#move: NamedResource,
+
+    /// The details of the version in which the Pokémon can learn the move.
+    pub version_group_details: Vec<PokemonMoveVersion>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PokemonMoveVersion {
+    /// The method by which the move is learned.
+    pub move_learn_method: NamedResource,
+
+    /// The version group in which the move is learned.
+    pub version_group: NamedResource,
+
+    /// The minimum level to learn the move.
+    pub level_learned_at: Integer,
+}
+
+/// How a Pokémon learns a move, the name of the `move-learn-method` endpoint resource.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum LearnMethod {
+    /// Learned when reaching a level.
+    LevelUp,
+
+    /// Taught by a TM, HM or TR.
+    Machine,
+
+    /// Inherited from a parent.
+    Egg,
+
+    /// Taught by a move tutor.
+    Tutor,
+
+    /// Any other method, such as `stadium-surfing-pikachu`.
+    Other(String),
+}
+
+impl LearnMethod {
+    /// Method with the name of the `move-learn-method` endpoint resource.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "level-up" => LearnMethod::LevelUp,
+            "machine" => LearnMethod::Machine,
+            "egg" => LearnMethod::Egg,
+            "tutor" => LearnMethod::Tutor,
+            other => LearnMethod::Other(other.to_string()),
+        }
+    }
+}
+
+/// Move learned by a Pokémon in a version group.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct LearnedMove {
+    /// The name of the move.
+    pub name: String,
+
+    /// How the move is learned.
+    pub method: LearnMethod,
+
+    /// The level the move is learned at, `0` unless learned by leveling up.
+    pub level: Integer,
+}
+
+/// Level-up move learned at another level.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LevelChange {
+    /// The name of the move.
+    pub name: String,
+
+    /// The level in the former version group.
+    pub from: Integer,
+
+    /// The level in the latter version group.
+    pub to: Integer,
+}
+
+/// Changes of a learnset between two version groups.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LearnsetDiff {
+    /// Moves learned only in the latter version group.
+    pub added: Vec<LearnedMove>,
+
+    /// Moves learned only in the former version group.
+    pub removed: Vec<LearnedMove>,
+
+    /// Level-up moves learned at different levels.
+    pub changed: Vec<LevelChange>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PokemonStat {
+    /// The stat the Pokémon has.
+    pub stat: NamedResource,
+
+    /// The effort points (EV) the Pokémon has in the stat.
+    pub effort: Integer,
+
+    /// The base value of the stat.
+    pub base_stat: Integer,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PokemonSprites {
+    /// The default depiction of this Pokémon from the front in battle.
+    pub front_default: Option<String>,
+
+    /// The shiny depiction of this Pokémon from the front in battle.
+    pub front_shiny: Option<String>,
+
+    /// The female depiction of this Pokémon from the front in battle.
+    pub front_female: Option<String>,
+
+    /// The shiny female depiction of this Pokémon from the front in battle.
+    pub front_shiny_female: Option<String>,
+
+    /// The default depiction of this Pokémon from the back in battle.
+    pub back_default: Option<String>,
+
+    /// The shiny depiction of this Pokémon from the back in battle.
+    pub back_shiny: Option<String>,
+
+    /// The female depiction of this Pokémon from the back in battle.
+    pub back_female: Option<String>,
+
+    /// The shiny female depiction of this Pokémon from the back in battle.
+    pub back_shiny_female: Option<String>,
+}
+
+/// Egg Groups are categories which determine which Pokémon are able to interbreed.
+/// Pokémon may belong to either one or two Egg Groups.
+/// Check out [Bulbapedia](http://bulbapedia.bulbagarden.net/wiki/Egg_Group) for greater detail.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EggGroup {
+    /// The identifier for this resource.
+    pub id: Id,
+
+    /// The name for this resource.
+    pub name: String,
+
+    /// The name of this resource listed in different languages.
+    pub names: Vec<Name>,
+
+    /// A list of all Pokémon species that are members of this egg group.
+    pub pokemon_species: Vec<NamedResource>,
+}
+
+impl HasNames for EggGroup {
+    fn names(&self) -> &[Name] {
+        &self.names
+    }
+}
+
+/// A Pokémon Species forms the basis for at least one Pokémon. Attributes of a Pokémon species
+/// are shared across all varieties of Pokémon within the species. A good example is Wormadam;
+/// Wormadam is the species which can be found in three different varieties,
+/// Wormadam-Trash, Wormadam-Sandy and Wormadam-Plant.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PokemonSpecies {
+    /// The identifier for this resource.
+    pub id: Id,
+
+    /// The name for this resource.
+    pub name: String,
+
+    /// The order in which species should be sorted. Based on National Dex order,
+    /// except families are grouped together and sorted by stage.
+    pub order: Integer,
+
+    /// The chance of this Pokémon being female, in eighths; or -1 for genderless.
+    pub gender_rate: Integer,
+
+    /// The base capture rate; up to 255. The higher the number, the easier the catch.
+    pub capture_rate: Integer,
+
+    /// The happiness when caught by a normal Pokéball; up to 255.
+    /// The higher the number, the happier the Pokémon.
+    pub base_happiness: Option<Integer>,
+
+    /// Whether or not this is a baby Pokémon.
+    pub is_baby: bool,
+
+    /// Whether or not this is a legendary Pokémon.
+    #[serde(default)]
+    pub is_legendary: bool,
+
+    /// Whether or not this is a mythical Pokémon.
+    #[serde(default)]
+    pub is_mythical: bool,
+
+    /// Initial hatch counter: one must walk 255 × (hatch_counter + 1) steps
+    /// before this Pokémon's egg hatches, unless utilizing bonuses like Flame Body's.
+    pub hatch_counter: Option<Integer>,
+
+    /// Whether or not this Pokémon has visual gender differences.
+    pub has_gender_differences: bool,
+
+    /// Whether or not this Pokémon has multiple forms and can switch between them.
+    pub forms_switchable: bool,
+
+    /// The rate at which this Pokémon species gains levels.
+    pub growth_rate: NamedResource,
+
+    /// A list of egg groups this Pokémon species is a member of.
+    pub egg_groups: Vec<NamedResource>,
+
+    /// The color of this Pokémon for Pokédex search.
+    pub color: NamedResource,
+
+    /// The shape of this Pokémon for Pokédex search.
+    pub shape: Option<NamedResource>,
+
+    /// The Pokémon species that evolves into this Pokemon_species.
+    pub evolves_from_species: Option<NamedResource>,
+
+    /// The evolution chain this Pokémon species is a member of.
+    pub evolution_chain: Option<Resource>,
+
+    /// The habitat this Pokémon species can be encountered in.
+    pub habitat: Option<NamedResource>,
+
+    /// The generation this Pokémon species was introduced in.
+    pub generation: NamedResource,
+
+    /// The name of this resource listed in different languages.
+    pub names: Vec<Name>,
+
+    /// A list of flavor text entries for this Pokémon species.
+    pub flavor_text_entries: Vec<FlavorText>,
+
+    /// Descriptions of different forms Pokémon take on within the Pokémon species.
+    pub form_descriptions: Vec<Description>,
+
+    /// The genus of this Pokémon species listed in multiple languages.
+    pub genera: Vec<Genus>,
+
+    /// A list of the Pokémon that exist within this Pokémon species.
+    pub varieties: Vec<PokemonSpeciesVariety>,
+}
+
+impl PokemonSpecies {
+    /// Whether the species has no gender.
+    pub fn is_genderless(&self) -> bool {
+        self.gender_rate < 0
+    }
+}
+
+impl HasNames for PokemonSpecies {
+    fn names(&self) -> &[Name] {
+        &self.names
+    }
+}
+
+impl HasDescriptions for PokemonSpecies {
+    fn descriptions(&self) -> &[Description] {
+        &self.form_descriptions
+    }
+}
+
+impl HasFlavorTexts for PokemonSpecies {
+    type FlavorText = FlavorText;
+
+    fn flavor_text_entries(&self) -> &[FlavorText] {
+        &self.flavor_text_entries
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Genus {
+    /// The localized genus for the referenced Pokémon species.
+    pub genus: String,
+
+    /// The language this genus is in.
+    pub language: NamedResource,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PokemonSpeciesVariety {
+    /// Whether this variety is the default variety.
+    pub is_default: bool,
+
+    /// The Pokémon variety.
+    pub pokemon: NamedResource,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LearnMethod, LevelChange, Pokemon};
+    use serde_json::json;
+
+    fn pokemon() -> Pokemon {
+        let details = |method: &str, version_group: &str, level: i32| {
+            json!({
+                "move_learn_method": { "name": method, "url": "" },
+                "version_group": { "name": version_group, "url": "" },
+                "level_learned_at": level
+            })
+        };
+        let learned = |name: &str, details: Vec<serde_json::Value>| json!({ "move": { "name": name, "url": "" }, "version_group_details": details });
+
+        serde_json::from_value(json!({
+            "id": 25,
+            "name": "pikachu",
+            "base_experience": 112,
+            "height": 4,
+            "is_default": true,
+            "order": 35,
+            "weight": 60,
+            "abilities": [],
+            "forms": [],
+            "game_indices": [],
+            "held_items": [],
+            "location_area_encounters": "",
+            "moves": [
+                learned("thunder-shock", vec![
+                    details("level-up", "red-blue", 1),
+                    details("level-up", "gold-silver", 1),
+                ]),
+                learned("thunder-wave", vec![
+                    details("level-up", "red-blue", 9),
+                    details("level-up", "gold-silver", 8),
+                    details("machine", "gold-silver", 0),
+                ]),
+                learned("quick-attack", vec![details("level-up", "red-blue", 16)]),
+                learned("thunder", vec![details("level-up", "red-blue", 43)]),
+                learned("thunderbolt", vec![
+                    details("machine", "red-blue", 0),
+                    details("machine", "gold-silver", 0),
+                ]),
+                learned("wish", vec![details("egg", "gold-silver", 0)]),
+                learned("headbutt", vec![details("tutor", "gold-silver", 0)]),
+            ],
+            "sprites": {},
+            "species": { "name": "pikachu", "url": "" },
+            "stats": [],
+            "types": []
+        }))
+        .unwrap()
+    }
+
+    fn names(moves: Vec<super::LearnedMove>) -> Vec<String> {
+        moves.into_iter().map(|m| m.name).collect()
+    }
+
+    #[test]
+    fn queries_learnset() {
+        let pikachu = pokemon();
+
+        assert_eq!(
+            names(pikachu.level_up_moves("red-blue", 20)),
+            vec!["thunder-shock", "thunder-wave", "quick-attack"]
+        );
+        assert_eq!(pikachu.level_up_moves("red-blue", 20)[1].level, 9);
+        assert_eq!(
+            names(pikachu.machine_moves("gold-silver")),
+            vec!["thunder-wave", "thunderbolt"]
+        );
+        assert_eq!(names(pikachu.egg_moves("gold-silver")), vec!["wish"]);
+        assert_eq!(names(pikachu.tutor_moves("gold-silver")), vec!["headbutt"]);
+        assert!(pikachu.egg_moves("red-blue").is_empty());
+        assert!(pikachu.learns("gold-silver", "wish"));
+        assert!(!pikachu.learns("red-blue", "wish"));
+        assert_eq!(pikachu.learnset("red-blue")[0].method, LearnMethod::LevelUp);
+    }
+
+    #[test]
+    fn diffs_version_groups() {
+        let diff = pokemon().learnset_diff("red-blue", "gold-silver");
+
+        assert_eq!(names(diff.added), vec!["thunder-wave", "wish", "headbutt"]);
+        assert_eq!(names(diff.removed), vec!["quick-attack", "thunder"]);
+        assert_eq!(
+            diff.changed,
+            vec![LevelChange {
+                name: "thunder-wave".into(),
+                from: 9,
+                to: 8
+            }]
+        );
+    }
+
+    #[test]
+    fn diffs_moves_learned_at_several_levels() {
+        let details = |version_group: &str, level: i32| {
+            json!({
+                "move_learn_method": { "name": "level-up", "url": "" },
+                "version_group": { "name": version_group, "url": "" },
+                "level_learned_at": level
+            })
+        };
+        let mut pikachu = pokemon();
+        pikachu.moves = serde_json::from_value(json!([{
+            "move": { "name": "growl", "url": "" },
+            "version_group_details": [
+                details("red-blue", 1),
+                details("red-blue", 5),
+                details("gold-silver", 1),
+                details("gold-silver", 6),
+            ]
+        }]))
+        .unwrap();
+
+        let diff = pikachu.learnset_diff("red-blue", "gold-silver");
+
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(
+            diff.changed,
+            vec![LevelChange {
+                name: "growl".into(),
+                from: 5,
+                to: 6
+            }]
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::common::Id;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Resource {
     pub url: String,
@@ -11,6 +13,53 @@ pub struct NamedResource {
     pub url: String,
 }
 
+impl Resource {
+    /// The identifier of the referenced resource, taken from its url.
+    pub fn id(&self) -> Option<Id> {
+        url_id(&self.url)
+    }
+}
+
+impl NamedResource {
+    /// The identifier of the referenced resource, taken from its url.
+    pub fn id(&self) -> Option<Id> {
+        url_id(&self.url)
+    }
+}
+
+/// Parses the last segment of the resource url, e.g. `3` of `https://pokeapi.co/api/v2/type/3/`.
+pub(crate) fn url_id(url: &str) -> Option<Id> {
+    url.trim_end_matches('/').rsplit('/').next()?.parse().ok()
+}
+
+/// Path of the resource after the API root, e.g. `berry/1` of `https://pokeapi.co/api/v2/berry/1/`.
+pub(crate) fn url_path(url: &str) -> &str {
+    url.split("/v2/")
+        .nth(1)
+        .unwrap_or(url)
+        .trim_end_matches('/')
+}
+
+/// Endpoint of the resource url, e.g. `berry` of `https://pokeapi.co/api/v2/berry/1/`.
+/// `None` for resource list urls.
+pub(crate) fn url_endpoint(url: &str) -> Option<&str> {
+    let path = url.split("/v2/").nth(1)?;
+    if path.contains('?') {
+        return None;
+    }
+
+    let mut segments = path.split('/').filter(|s| !s.is_empty());
+    match (segments.next(), segments.next(), segments.next()) {
+        (Some(endpoint), Some(_), None) => Some(endpoint),
+        _ => None,
+    }
+}
+
+/// Url without the trailing slash, so `berry/1` and `berry/1/` are the same resource.
+pub(crate) fn normalize_url(url: &str) -> String {
+    url.trim_end_matches('/').to_string()
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ResourceList {
     pub count: usize,
@@ -26,3 +75,37 @@ pub struct NamedResourceList {
     pub previous: Option<String>,
     pub results: Vec<NamedResource>,
 }
+
+#[cfg(test)]
+mod test {
+    use super::{normalize_url, url_endpoint, url_id, url_path};
+
+    const BASE: &str = "https://pokeapi.co/api/v2";
+
+    #[test]
+    fn parses_resource_urls() {
+        assert_eq!(url_id(&format!("{}/berry/12/", BASE)), Some(12));
+        assert_eq!(url_id(&format!("{}/berry-firmness/3", BASE)), Some(3));
+        assert_eq!(url_id(&format!("{}/berry/cheri/", BASE)), None);
+
+        assert_eq!(url_path(&format!("{}/berry/1/", BASE)), "berry/1");
+        assert_eq!(
+            normalize_url(&format!("{}/berry/1/", BASE)),
+            format!("{}/berry/1", BASE)
+        );
+    }
+
+    #[test]
+    fn finds_resource_endpoints() {
+        assert_eq!(url_endpoint(&format!("{}/berry/1/", BASE)), Some("berry"));
+        assert_eq!(
+            url_endpoint(&format!("{}/berry-firmness/soft", BASE)),
+            Some("berry-firmness")
+        );
+        assert_eq!(url_endpoint(&format!("{}/berry/", BASE)), None);
+        assert_eq!(
+            url_endpoint(&format!("{}/berry?offset=0&limit=20", BASE)),
+            None
+        );
+    }
+}
@@ -0,0 +1,561 @@
+//! Local HTTP server exposing a PokeAPI compatible API, e.g. for developing frontends offline.
+//!
+//! The server answers `GET` requests using the PokeAPI url layout:
+//! `/api/v2/` lists the endpoints, `/api/v2/{endpoint}/?offset=&limit=` gives paginated
+//! resource lists with `count`, `next` and `previous` like `NamedResourceList`, and
+//! `/api/v2/{endpoint}/{id or name}/` gives resources. Urls inside the documents point
+//! at the host the request was sent to when it names the bound address or `localhost`,
+//! at the bound address otherwise. Resources come from a `Backend`:
+//!
+//! * `SnapshotBackend` reads the directory written by `Mirror`,
+//! * `CacheBackend` reads responses cached by `ApiClient`, e.g. in a `FileCache`,
+//! * `SqliteStore` with the `sqlite` feature serves the responses it stores like `CacheBackend`,
+//!   and lists every stored resource of the endpoint.
+//!
+//! ```no_run
+//! # fn example() -> pokemon_api::Result<()> {
+//! use pokemon_api::server::{Server, SnapshotBackend};
+//!
+//! let server = Server::start("127.0.0.1:8000", SnapshotBackend::open("pokeapi-data")?)?;
+//! println!("Serving {}", server.base_url());
+//! server.join();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use serde_json::{Map, Value};
+
+use crate::api::v2::cache::Cache;
+use crate::api::v2::endpoint::MAX_PAGINATION_LIMIT;
+use crate::api::POKE_API_BASE_URL;
+use crate::http::{self, Request, Response};
+use crate::mirror::read_snapshot;
+use crate::models::v2::resource::{url_id, NamedResourceList};
+use crate::Result;
+
+/// Address the server listens on by default.
+pub const DEFAULT_ADDR: &str = "127.0.0.1:8000";
+
+/// Source of the resources served by `Server`.
+pub trait Backend: Send + Sync {
+    /// Names of the endpoints with resources, e.g. `berry`.
+    fn endpoints(&self) -> Result<Vec<String>>;
+
+    /// Ids and names of all resources of the endpoint ordered by id,
+    /// `None` if the endpoint is unknown.
+    fn resources(&self, endpoint: &str) -> Result<Option<Vec<(i64, String)>>>;
+
+    /// Document of the resource given its id or name, `None` if unknown.
+    fn resource(&self, endpoint: &str, key: &str) -> Result<Option<Value>>;
+}
+
+/// Resources of a single endpoint, ordered by id.
+type Resources = BTreeMap<i64, Value>;
+
+/// Finds the resource by its id or name.
+fn find<'r>(resources: &'r Resources, key: &str) -> Option<&'r Value> {
+    match key.parse::<i64>() {
+        Ok(id) => resources.get(&id),
+        Err(_) => resources
+            .values()
+            .find(|res| res["name"].as_str() == Some(key)),
+    }
+}
+
+/// Backend serving the directory written by `Mirror`, loaded in memory when opened.
+#[derive(Clone, Debug, Default)]
+pub struct SnapshotBackend {
+    endpoints: BTreeMap<String, Resources>,
+}
+
+impl SnapshotBackend {
+    /// Loads every resource of the directory, e.g. `{dir}/api/v2/berry/1/index.json`.
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let mut endpoints: BTreeMap<String, Resources> = BTreeMap::new();
+        for (endpoint, resource) in read_snapshot(dir)? {
+            let (id, _) = http::resource_key(&resource);
+            endpoints.entry(endpoint).or_default().insert(id, resource);
+        }
+        Ok(Self { endpoints })
+    }
+
+    /// Number of loaded resources.
+    pub fn len(&self) -> usize {
+        self.endpoints.values().map(BTreeMap::len).sum()
+    }
+
+    /// Whether no resources were loaded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Backend for SnapshotBackend {
+    fn endpoints(&self) -> Result<Vec<String>> {
+        Ok(self.endpoints.keys().cloned().collect())
+    }
+
+    fn resources(&self, endpoint: &str) -> Result<Option<Vec<(i64, String)>>> {
+        Ok(self
+            .endpoints
+            .get(endpoint)
+            .map(|resources| resources.values().map(http::resource_key).collect()))
+    }
+
+    fn resource(&self, endpoint: &str, key: &str) -> Result<Option<Value>> {
+        Ok(self
+            .endpoints
+            .get(endpoint)
+            .and_then(|resources| find(resources, key))
+            .cloned())
+    }
+}
+
+/// Backend serving the responses cached by `ApiClient`.
+///
+/// Resources are found under the urls the client requests them by, so only resources
+/// fetched before are served. Lists are served for endpoints whose complete resource list
+/// was cached, as `all_paginated(0, MAX_PAGINATION_LIMIT)`, search or `Mirror` do.
+/// Caches can not enumerate their responses, so the endpoints are not listed.
+pub struct CacheBackend<C> {
+    cache: C,
+    url: String,
+}
+
+impl<C: Cache> CacheBackend<C> {
+    /// Creates backend reading the responses of `https://pokeapi.co/api/v2` from the cache.
+    pub fn new(cache: C) -> Self {
+        Self {
+            cache,
+            url: format!("{}/v2", POKE_API_BASE_URL),
+        }
+    }
+
+    /// Reads responses of the API at the base url instead,
+    /// the one given to `ApiClient::with_base_url`.
+    pub fn with_base_url<T: Into<String>>(mut self, url: T) -> Self {
+        self.url = format!("{}/v2", url.into().trim_end_matches('/'));
+        self
+    }
+
+    fn get(&self, path: &str) -> Result<Option<Value>> {
+        let url = format!("{}/{}", self.url, path);
+        match self
+            .cache
+            .get(&url)
+            .or_else(|| self.cache.get(&format!("{}/", url)))
+        {
+            Some(body) => Ok(Some(serde_json::from_str(&body)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<C: Cache> Backend for CacheBackend<C> {
+    fn endpoints(&self) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn resources(&self, endpoint: &str) -> Result<Option<Vec<(i64, String)>>> {
+        let complete = format!("{}?offset=0&limit={}", endpoint, MAX_PAGINATION_LIMIT);
+        for path in [complete.as_str(), endpoint].iter() {
+            let list: NamedResourceList = match self.get(path)? {
+                Some(value) => serde_json::from_value(value)?,
+                None => continue,
+            };
+            if list.results.len() < list.count {
+                continue;
+            }
+
+            let mut resources: Vec<(i64, String)> = list
+                .results
+                .into_iter()
+                .filter_map(|res| Some((i64::from(url_id(&res.url)?), res.name)))
+                .collect();
+            resources.sort();
+            return Ok(Some(resources));
+        }
+
+        Ok(None)
+    }
+
+    fn resource(&self, endpoint: &str, key: &str) -> Result<Option<Value>> {
+        if let Some(value) = self.get(&format!("{}/{}", endpoint, key))? {
+            return Ok(Some(value));
+        }
+
+        // Resources fetched by id are found by name through the resource list.
+        let id = self
+            .resources(endpoint)?
+            .and_then(|resources| resources.into_iter().find(|(_, name)| name == key))
+            .map(|(id, _)| id);
+        match id {
+            Some(id) => self.get(&format!("{}/{}", endpoint, id)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use rusqlite::params;
+    use serde_json::Value;
+
+    use super::{Backend, CacheBackend, Resources};
+    use crate::http;
+    use crate::models::v2::resource::url_endpoint;
+    use crate::sqlite::SqliteStore;
+    use crate::Result;
+
+    impl SqliteStore {
+        /// Resources of the endpoint among the stored responses, by id.
+        /// Reads every stored response of the endpoint, so it is only used for lists.
+        fn stored_resources(&self, endpoint: &str) -> Result<Resources> {
+            let conn = self.connection();
+            let mut statement = conn.prepare("SELECT url, body FROM response WHERE url LIKE ?1")?;
+            let rows = statement.query_map(params![format!("%/v2/{}/%", endpoint)], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+
+            let mut resources = Resources::new();
+            for row in rows {
+                let (url, body) = row?;
+                if url_endpoint(&url) != Some(endpoint) {
+                    continue;
+                }
+                // The same resource may be stored under its id and its name.
+                if let Ok(value) = serde_json::from_str::<Value>(&body) {
+                    resources.insert(http::resource_key(&value).0, value);
+                }
+            }
+            Ok(resources)
+        }
+    }
+
+    impl Backend for SqliteStore {
+        fn endpoints(&self) -> Result<Vec<String>> {
+            let conn = self.connection();
+            let mut statement = conn.prepare("SELECT url FROM response")?;
+            let urls = statement.query_map(params![], |row| row.get::<_, String>(0))?;
+
+            let mut endpoints = Vec::new();
+            for url in urls {
+                if let Some(endpoint) = url_endpoint(&url?) {
+                    endpoints.push(endpoint.to_string());
+                }
+            }
+            endpoints.sort();
+            endpoints.dedup();
+            Ok(endpoints)
+        }
+
+        fn resources(&self, endpoint: &str) -> Result<Option<Vec<(i64, String)>>> {
+            let resources = self.stored_resources(endpoint)?;
+            if resources.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some(resources.values().map(http::resource_key).collect()))
+        }
+
+        /// Looks the resource up by the url it was stored under, like `CacheBackend` does.
+        fn resource(&self, endpoint: &str, key: &str) -> Result<Option<Value>> {
+            CacheBackend::new(self).resource(endpoint, key)
+        }
+    }
+}
+
+/// Local HTTP server serving the resources of a backend in the PokeAPI url layout.
+/// The server is stopped when dropped.
+pub struct Server {
+    addr: SocketAddr,
+    stopped: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Server {
+    /// Starts the server on the address, e.g. `127.0.0.1:8000` or `127.0.0.1:0` for a free port.
+    pub fn start<A: ToSocketAddrs, B: Backend + 'static>(addr: A, backend: B) -> Result<Server> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let stopped = Arc::new(AtomicBool::new(false));
+        let backend = Arc::new(backend);
+
+        let thread = http::serve(
+            listener,
+            stopped.clone(),
+            Arc::new(move |request| handle(request, backend.as_ref(), addr)),
+        );
+
+        Ok(Server {
+            addr,
+            stopped,
+            thread: Some(thread),
+        })
+    }
+
+    /// Address the server listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Root url of the served API, to be passed to `ApiClient::with_base_url`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}/api", self.addr)
+    }
+
+    /// Blocks the current thread while the server runs.
+    pub fn join(mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wake up the accepting thread, so it notices the server is stopped.
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+/// Produces response for the request, answering failures of the backend with
+/// `500 Internal Server Error`.
+fn handle(request: &Request, backend: &dyn Backend, addr: SocketAddr) -> Response {
+    if request.method != "GET" {
+        return Response::status(405);
+    }
+
+    // Urls point at the host known to the client, which may differ from the bound address,
+    // but only when it names the server; the header is sent by the client and not trusted.
+    let base = match request.host {
+        Some(ref host) if names_server(host, addr) => format!("http://{}/api", host),
+        _ => format!("http://{}/api", addr),
+    };
+
+    route(request, backend, &base).unwrap_or_else(|_| Response::status(500))
+}
+
+/// Whether the `Host` header names the bound address, `localhost` on its port,
+/// or a loopback address when the server listens on every interface.
+fn names_server(host: &str, addr: SocketAddr) -> bool {
+    match host.parse::<SocketAddr>() {
+        Ok(host) => {
+            host.port() == addr.port()
+                && (host.ip() == addr.ip() || addr.ip().is_unspecified() && host.ip().is_loopback())
+        }
+        Err(_) => host == format!("localhost:{}", addr.port()),
+    }
+}
+
+fn route(request: &Request, backend: &dyn Backend, base: &str) -> Result<Response> {
+    let path = request.path();
+    let segments: Vec<&str> = path.split('/').filter(|seg| !seg.is_empty()).collect();
+
+    let response = match segments.as_slice() {
+        ["api", "v2"] => {
+            let endpoints: Map<String, Value> = backend
+                .endpoints()?
+                .into_iter()
+                .map(|endpoint| {
+                    let url = format!("{}/v2/{}/", base, endpoint);
+                    (endpoint, Value::String(url))
+                })
+                .collect();
+            Response::json(&Value::Object(endpoints))
+        }
+        ["api", "v2", endpoint] => match backend.resources(endpoint)? {
+            Some(resources) => {
+                Response::json(&http::page(&resources, endpoint, request.query(), base))
+            }
+            None => Response::status(404),
+        },
+        ["api", "v2", endpoint, key] => match backend.resource(endpoint, key)? {
+            Some(mut resource) => {
+                http::rewrite(&mut resource, base);
+                Response::json(&resource)
+            }
+            None => Response::status(404),
+        },
+        _ => Response::status(404),
+    };
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{handle, names_server, CacheBackend, Server, SnapshotBackend};
+    use crate::api::v2::cache::{Cache, MemoryCache};
+    use crate::api::v2::client::ApiClient;
+    use crate::api::v2::endpoint::{ApiEndpoint, MAX_PAGINATION_LIMIT};
+    use crate::api::v2::resource::ApiNamedResourceList;
+    use crate::http::Request;
+    use serde_json::{json, Value};
+    use std::env;
+    use std::fs;
+
+    fn firmness(id: usize, name: &str, host: &str) -> Value {
+        json!({
+            "id": id,
+            "name": name,
+            "berries": [{ "name": name, "url": format!("{}/api/v2/berry/{}/", host, id) }],
+            "names": []
+        })
+    }
+
+    const NAMES: [&str; 5] = ["very-soft", "soft", "hard", "very-hard", "super-hard"];
+
+    #[tokio::test]
+    async fn serves_snapshot() {
+        let dir = env::temp_dir().join(format!("pokemon-api-server-{}", std::process::id()));
+        for (id, name) in NAMES.iter().enumerate() {
+            let path = dir.join(format!("api/v2/berry-firmness/{}", id + 1));
+            fs::create_dir_all(&path).unwrap();
+            let body = firmness(id + 1, name, "").to_string();
+            fs::write(path.join("index.json"), body).unwrap();
+        }
+
+        let backend = SnapshotBackend::open(&dir).unwrap();
+        assert_eq!(backend.len(), 5);
+        let server = Server::start("127.0.0.1:0", backend).unwrap();
+        let client = ApiClient::new().unwrap().with_base_url(server.base_url());
+        let firmnesses = client.berry_firmnesses();
+
+        let first = firmnesses.all_paginated(0, 2).await.unwrap();
+        assert_eq!(first.count(), 5);
+        assert!(first.previous_list().await.unwrap().is_none());
+        let third = first
+            .next_list()
+            .await
+            .unwrap()
+            .unwrap()
+            .next_list()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(third.resources().len(), 1);
+        assert!(third.next_list().await.unwrap().is_none());
+
+        let hard = firmnesses.get_by_name("hard").await.unwrap();
+        assert_eq!(hard.id, 3);
+        assert_eq!(
+            hard.berries[0].url,
+            format!("{}/v2/berry/3/", server.base_url())
+        );
+        assert!(firmnesses.get_by_id(42).await.is_err());
+        assert!(client.berries().all().await.is_err());
+
+        let root: Value = client
+            .request_object(format!("{}/v2/", server.base_url()))
+            .await
+            .unwrap();
+        assert_eq!(
+            root["berry-firmness"],
+            format!("{}/v2/berry-firmness/", server.base_url())
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn serves_cache() {
+        let cache = MemoryCache::new();
+        let api = "https://pokeapi.co/api/v2";
+        let results: Vec<Value> = NAMES
+            .iter()
+            .enumerate()
+            .map(|(id, name)| json!({ "name": name, "url": format!("{}/berry-firmness/{}/", api, id + 1) }))
+            .collect();
+        cache.put(
+            &format!(
+                "{}/berry-firmness?offset=0&limit={}",
+                api, MAX_PAGINATION_LIMIT
+            ),
+            &json!({ "count": 5, "next": null, "previous": null, "results": results }).to_string(),
+        );
+        cache.put(
+            &format!("{}/berry-firmness/2", api),
+            &firmness(2, "soft", "https://pokeapi.co").to_string(),
+        );
+
+        let server = Server::start("127.0.0.1:0", CacheBackend::new(cache)).unwrap();
+        let client = ApiClient::new().unwrap().with_base_url(server.base_url());
+        let firmnesses = client.berry_firmnesses();
+
+        let list = firmnesses.all().await.unwrap();
+        assert_eq!(list.count(), 5);
+        let soft = firmnesses.get_by_name("soft").await.unwrap();
+        assert_eq!(soft.id, 2);
+        assert_eq!(
+            soft.berries[0].url,
+            format!("{}/v2/berry/2/", server.base_url())
+        );
+        assert!(firmnesses.get_by_id(3).await.is_err());
+    }
+
+    #[test]
+    fn ignores_foreign_hosts() {
+        let addr = "127.0.0.1:8000".parse().unwrap();
+        assert!(names_server("127.0.0.1:8000", addr));
+        assert!(names_server("localhost:8000", addr));
+        assert!(!names_server("localhost:9000", addr));
+        assert!(!names_server("pokeapi.example:8000", addr));
+        assert!(!names_server("10.0.0.1:8000", addr));
+        assert!(names_server("[::1]:8000", "[::]:8000".parse().unwrap()));
+
+        let cache = MemoryCache::new();
+        cache.put(
+            "https://pokeapi.co/api/v2/berry-firmness/2",
+            &firmness(2, "soft", "https://pokeapi.co").to_string(),
+        );
+        let backend = CacheBackend::new(cache);
+        let url = |host: &str| {
+            let request = Request {
+                method: "GET".to_string(),
+                target: "/api/v2/berry-firmness/2/".to_string(),
+                host: Some(host.to_string()),
+                body: Vec::new(),
+            };
+            let response = handle(&request, &backend, addr);
+            let soft: Value = serde_json::from_slice(&response.body).unwrap();
+            soft["berries"][0]["url"].as_str().unwrap().to_string()
+        };
+
+        assert_eq!(
+            url("localhost:8000"),
+            "http://localhost:8000/api/v2/berry/2/"
+        );
+        assert_eq!(url("evil.example"), "http://127.0.0.1:8000/api/v2/berry/2/");
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn serves_sqlite() {
+        use crate::sqlite::SqliteStore;
+
+        let store = SqliteStore::in_memory().unwrap();
+        let api = "https://pokeapi.co/api/v2";
+        for (id, name) in NAMES.iter().enumerate().take(3) {
+            let body = firmness(id + 1, name, "https://pokeapi.co").to_string();
+            store.put(&format!("{}/berry-firmness/{}", api, id + 1), &body);
+            store.put(&format!("{}/berry-firmness/{}", api, name), &body);
+        }
+
+        let server = Server::start("127.0.0.1:0", store).unwrap();
+        let client = ApiClient::new().unwrap().with_base_url(server.base_url());
+        let firmnesses = client.berry_firmnesses();
+
+        let list = firmnesses.all_paginated(1, 1).await.unwrap();
+        assert_eq!(list.count(), 3);
+        assert_eq!(list.resources()[0].name(), "soft");
+        assert_eq!(firmnesses.get_by_id(3).await.unwrap().name, "hard");
+    }
+}
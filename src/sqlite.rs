@@ -0,0 +1,558 @@
+//! SQLite persistence of fetched models.
+//!
+//! `SqliteStore` keeps every model in its own table, with join tables for lists such as
+//! berry flavors or the berries of a firmness, so offline tools can query the data with SQL:
+//!
+//! ```sql
+//! SELECT berry.name, berry_flavor_potency.potency
+//! FROM berry
+//! JOIN berry_flavor_potency ON berry_flavor_potency.berry_id = berry.id
+//! WHERE berry.firmness = 'soft' AND berry_flavor_potency.flavor = 'spicy'
+//! ORDER BY berry_flavor_potency.potency DESC;
+//! ```
+//!
+//! The store is also a `Cache` of `ApiClient`: raw responses are kept in the `response` table
+//! and responses of known endpoints are stored as models on the way.
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::de::DeserializeOwned;
+
+use crate::api::v2::cache::Cache;
+use crate::models::v2::berry::{Berry, BerryFirmness, BerryFlavor};
+use crate::models::v2::moves::Move;
+use crate::models::v2::pokemon::{Nature, Pokemon, Type, TypeRelations};
+use crate::models::v2::resource::url_endpoint;
+use crate::Result;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS response (
+    url TEXT PRIMARY KEY,
+    body TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS berry (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    growth_time INTEGER NOT NULL,
+    max_harvest INTEGER NOT NULL,
+    natural_gift_power INTEGER NOT NULL,
+    size INTEGER NOT NULL,
+    smoothness INTEGER NOT NULL,
+    soil_dryness INTEGER NOT NULL,
+    firmness TEXT NOT NULL,
+    item TEXT NOT NULL,
+    natural_gift_type TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS berry_flavor_potency (
+    berry_id INTEGER NOT NULL REFERENCES berry (id),
+    flavor TEXT NOT NULL,
+    potency INTEGER NOT NULL,
+    PRIMARY KEY (berry_id, flavor)
+);
+CREATE TABLE IF NOT EXISTS berry_firmness (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE
+);
+CREATE TABLE IF NOT EXISTS berry_firmness_berry (
+    firmness_id INTEGER NOT NULL REFERENCES berry_firmness (id),
+    berry TEXT NOT NULL,
+    PRIMARY KEY (firmness_id, berry)
+);
+CREATE TABLE IF NOT EXISTS berry_flavor (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    contest_type TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS type (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    generation TEXT NOT NULL,
+    move_damage_class TEXT
+);
+CREATE TABLE IF NOT EXISTS type_damage_relation (
+    type_id INTEGER NOT NULL REFERENCES type (id),
+    relation TEXT NOT NULL,
+    other TEXT NOT NULL,
+    PRIMARY KEY (type_id, relation, other)
+);
+CREATE TABLE IF NOT EXISTS pokemon (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    species TEXT NOT NULL,
+    base_experience INTEGER,
+    height INTEGER NOT NULL,
+    weight INTEGER NOT NULL,
+    is_default INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS pokemon_type (
+    pokemon_id INTEGER NOT NULL REFERENCES pokemon (id),
+    slot INTEGER NOT NULL,
+    type TEXT NOT NULL,
+    PRIMARY KEY (pokemon_id, slot)
+);
+CREATE TABLE IF NOT EXISTS pokemon_stat (
+    pokemon_id INTEGER NOT NULL REFERENCES pokemon (id),
+    stat TEXT NOT NULL,
+    base_stat INTEGER NOT NULL,
+    effort INTEGER NOT NULL,
+    PRIMARY KEY (pokemon_id, stat)
+);
+CREATE TABLE IF NOT EXISTS pokemon_ability (
+    pokemon_id INTEGER NOT NULL REFERENCES pokemon (id),
+    slot INTEGER NOT NULL,
+    ability TEXT NOT NULL,
+    is_hidden INTEGER NOT NULL,
+    PRIMARY KEY (pokemon_id, slot)
+);
+CREATE TABLE IF NOT EXISTS nature (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    increased_stat TEXT,
+    decreased_stat TEXT,
+    likes_flavor TEXT,
+    hates_flavor TEXT
+);
+CREATE TABLE IF NOT EXISTS move (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    type TEXT NOT NULL,
+    damage_class TEXT NOT NULL,
+    power INTEGER,
+    accuracy INTEGER,
+    pp INTEGER,
+    priority INTEGER NOT NULL,
+    generation TEXT NOT NULL
+);
+";
+
+/// Model stored in its own table of `SqliteStore`.
+pub trait StoredModel: DeserializeOwned {
+    /// Name of the API endpoint of the model.
+    const ENDPOINT: &'static str;
+
+    /// Replaces the rows of the model, including its join table rows.
+    fn store(&self, tx: &Transaction) -> rusqlite::Result<()>;
+}
+
+/// SQLite database of fetched models, usable as a cache of `ApiClient`.
+///
+/// Pass the store wrapped in `Arc` to `ApiClient::with_cache` to keep querying it.
+#[derive(Debug)]
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Opens the database file, creating the tables if needed.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Opens a database living in memory only.
+    pub fn in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Connection to the database for running queries.
+    pub fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap()
+    }
+
+    /// Stores the model, replacing the previous version of it.
+    pub fn insert<M: StoredModel>(&self, model: &M) -> Result<()> {
+        let mut conn = self.connection();
+        let tx = conn.transaction()?;
+        model.store(&tx)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Stores the response body as a model of the endpoint.
+    /// Returns `false` when models of the endpoint have no table.
+    pub fn insert_json(&self, endpoint: &str, body: &str) -> Result<bool> {
+        match endpoint {
+            Berry::ENDPOINT => self.insert_parsed::<Berry>(body),
+            BerryFirmness::ENDPOINT => self.insert_parsed::<BerryFirmness>(body),
+            BerryFlavor::ENDPOINT => self.insert_parsed::<BerryFlavor>(body),
+            Type::ENDPOINT => self.insert_parsed::<Type>(body),
+            Pokemon::ENDPOINT => self.insert_parsed::<Pokemon>(body),
+            Nature::ENDPOINT => self.insert_parsed::<Nature>(body),
+            Move::ENDPOINT => self.insert_parsed::<Move>(body),
+            _ => Ok(false),
+        }
+    }
+
+    fn insert_parsed<M: StoredModel>(&self, body: &str) -> Result<bool> {
+        self.insert(&serde_json::from_str::<M>(body)?)?;
+        Ok(true)
+    }
+}
+
+impl Cache for SqliteStore {
+    fn get(&self, url: &str) -> Option<String> {
+        self.connection()
+            .query_row(
+                "SELECT body FROM response WHERE url = ?1",
+                params![url],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    fn put(&self, url: &str, body: &str) {
+        let stored = self.connection().execute(
+            "INSERT OR REPLACE INTO response (url, body) VALUES (?1, ?2)",
+            params![url, body],
+        );
+
+        // Resource lists and unknown shapes are only kept as responses.
+        if let (Ok(_), Some(endpoint)) = (stored, url_endpoint(url)) {
+            let _ = self.insert_json(endpoint, body);
+        }
+    }
+}
+
+impl StoredModel for Berry {
+    const ENDPOINT: &'static str = "berry";
+
+    fn store(&self, tx: &Transaction) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT OR REPLACE INTO berry (id, name, growth_time, max_harvest, natural_gift_power,
+                size, smoothness, soil_dryness, firmness, item, natural_gift_type)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                self.id,
+                self.name,
+                self.growth_time,
+                self.max_harvest,
+                self.natural_gift_power,
+                self.size,
+                self.smoothness,
+                self.soil_dryness,
+                self.firmness.name,
+                self.item.name,
+                self.natural_gift_type.name,
+            ],
+        )?;
+
+        tx.execute(
+            "DELETE FROM berry_flavor_potency WHERE berry_id = ?1",
+            params![self.id],
+        )?;
+        for flavor in self.flavors.iter() {
+            tx.execute(
+                "INSERT INTO berry_flavor_potency (berry_id, flavor, potency) VALUES (?1, ?2, ?3)",
+                params![self.id, flavor.flavor.name, flavor.potency],
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl StoredModel for BerryFirmness {
+    const ENDPOINT: &'static str = "berry-firmness";
+
+    fn store(&self, tx: &Transaction) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT OR REPLACE INTO berry_firmness (id, name) VALUES (?1, ?2)",
+            params![self.id, self.name],
+        )?;
+
+        tx.execute(
+            "DELETE FROM berry_firmness_berry WHERE firmness_id = ?1",
+            params![self.id],
+        )?;
+        for berry in self.berries.iter() {
+            tx.execute(
+                "INSERT INTO berry_firmness_berry (firmness_id, berry) VALUES (?1, ?2)",
+                params![self.id, berry.name],
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl StoredModel for BerryFlavor {
+    const ENDPOINT: &'static str = "berry-flavor";
+
+    fn store(&self, tx: &Transaction) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT OR REPLACE INTO berry_flavor (id, name, contest_type) VALUES (?1, ?2, ?3)",
+            params![self.id, self.name, self.contest_type.name],
+        )?;
+        Ok(())
+    }
+}
+
+impl StoredModel for Type {
+    const ENDPOINT: &'static str = "type";
+
+    fn store(&self, tx: &Transaction) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT OR REPLACE INTO type (id, name, generation, move_damage_class)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                self.id,
+                self.name,
+                self.generation.name,
+                self.move_damage_class.as_ref().map(|c| &c.name),
+            ],
+        )?;
+
+        tx.execute(
+            "DELETE FROM type_damage_relation WHERE type_id = ?1",
+            params![self.id],
+        )?;
+        let TypeRelations {
+            no_damage_to,
+            half_damage_to,
+            double_damage_to,
+            no_damage_from,
+            half_damage_from,
+            double_damage_from,
+        } = &self.damage_relations;
+        for (relation, others) in [
+            ("no_damage_to", no_damage_to),
+            ("half_damage_to", half_damage_to),
+            ("double_damage_to", double_damage_to),
+            ("no_damage_from", no_damage_from),
+            ("half_damage_from", half_damage_from),
+            ("double_damage_from", double_damage_from),
+        ]
+        .iter()
+        {
+            for other in others.iter() {
+                tx.execute(
+                    "INSERT INTO type_damage_relation (type_id, relation, other) VALUES (?1, ?2, ?3)",
+                    params![self.id, relation, other.name],
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl StoredModel for Pokemon {
+    const ENDPOINT: &'static str = "pokemon";
+
+    fn store(&self, tx: &Transaction) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT OR REPLACE INTO pokemon (id, name, species, base_experience, height, weight,
+                is_default)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                self.id,
+                self.name,
+                self.species.name,
+                self.base_experience,
+                self.height,
+                self.weight,
+                self.is_default,
+            ],
+        )?;
+
+        for table in ["pokemon_type", "pokemon_stat", "pokemon_ability"].iter() {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE pokemon_id = ?1", table),
+                params![self.id],
+            )?;
+        }
+        for t in self.types.iter() {
+            tx.execute(
+                "INSERT INTO pokemon_type (pokemon_id, slot, type) VALUES (?1, ?2, ?3)",
+                params![self.id, t.slot, t.r#type.name],
+            )?;
+        }
+        for stat in self.stats.iter() {
+            tx.execute(
+                "INSERT INTO pokemon_stat (pokemon_id, stat, base_stat, effort)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![self.id, stat.stat.name, stat.base_stat, stat.effort],
+            )?;
+        }
+        for ability in self.abilities.iter() {
+            tx.execute(
+                "INSERT INTO pokemon_ability (pokemon_id, slot, ability, is_hidden)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    self.id,
+                    ability.slot,
+                    ability.ability.name,
+                    ability.is_hidden
+                ],
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl StoredModel for Nature {
+    const ENDPOINT: &'static str = "nature";
+
+    fn store(&self, tx: &Transaction) -> rusqlite::Result<()> {
+        let name = |r: &Option<crate::models::v2::resource::NamedResource>| {
+            r.as_ref().map(|r| r.name.clone())
+        };
+
+        tx.execute(
+            "INSERT OR REPLACE INTO nature (id, name, increased_stat, decreased_stat,
+                likes_flavor, hates_flavor)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                self.id,
+                self.name,
+                name(&self.increased_stat),
+                name(&self.decreased_stat),
+                name(&self.likes_flavor),
+                name(&self.hates_flavor),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl StoredModel for Move {
+    const ENDPOINT: &'static str = "move";
+
+    fn store(&self, tx: &Transaction) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT OR REPLACE INTO move (id, name, type, damage_class, power, accuracy, pp,
+                priority, generation)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                self.id,
+                self.name,
+                self.r#type.name,
+                self.damage_class.name,
+                self.power,
+                self.accuracy,
+                self.pp,
+                self.priority,
+                self.generation.name,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SqliteStore;
+    use crate::api::v2::cache::Cache;
+    use crate::api::v2::endpoint::ApiEndpoint;
+    use crate::models::v2::berry::{Berry, BerryFirmness};
+    use crate::testing::MockServer;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    fn berry(id: usize, name: &str, firmness: &str, spicy: i32) -> Value {
+        json!({
+            "id": id,
+            "name": name,
+            "growth_time": 3,
+            "max_harvest": 5,
+            "natural_gift_power": 60,
+            "size": 20,
+            "smoothness": 25,
+            "soil_dryness": 15,
+            "firmness": { "name": firmness, "url": "" },
+            "flavors": [
+                { "potency": spicy, "flavor": { "name": "spicy", "url": "" } },
+                { "potency": 0, "flavor": { "name": "dry", "url": "" } }
+            ],
+            "item": { "name": format!("{}-berry", name), "url": "" },
+            "natural_gift_type": { "name": "fire", "url": "" }
+        })
+    }
+
+    #[test]
+    fn joins_normalized_tables() {
+        let store = SqliteStore::in_memory().unwrap();
+        for (id, name, firmness, spicy) in [
+            (1, "cheri", "soft", 10),
+            (8, "persim", "hard", 10),
+            (14, "spelon", "soft", 30),
+            (3, "pecha", "very-soft", 0),
+        ]
+        .iter()
+        {
+            let berry: Berry = serde_json::from_value(berry(*id, name, firmness, *spicy)).unwrap();
+            store.insert(&berry).unwrap();
+        }
+        let soft: BerryFirmness = serde_json::from_value(json!({
+            "id": 2,
+            "name": "soft",
+            "berries": [{ "name": "cheri", "url": "" }, { "name": "spelon", "url": "" }],
+            "names": []
+        }))
+        .unwrap();
+        store.insert(&soft).unwrap();
+
+        // Storing again replaces the rows instead of duplicating them.
+        store.insert(&soft).unwrap();
+
+        let conn = store.connection();
+        let mut statement = conn
+            .prepare(
+                "SELECT berry.name, potency.potency FROM berry
+                 JOIN berry_flavor_potency AS potency ON potency.berry_id = berry.id
+                 JOIN berry_firmness_berry AS member ON member.berry = berry.name
+                 JOIN berry_firmness AS firmness ON firmness.id = member.firmness_id
+                 WHERE firmness.name = 'soft' AND potency.flavor = 'spicy'
+                 ORDER BY potency.potency DESC",
+            )
+            .unwrap();
+        let rows: Vec<(String, i32)> = statement
+            .query_map(rusqlite::NO_PARAMS, |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .map(|row| row.unwrap())
+            .collect();
+
+        assert_eq!(
+            rows,
+            vec![("spelon".to_string(), 30), ("cheri".to_string(), 10)]
+        );
+    }
+
+    #[tokio::test]
+    async fn caches_client_responses() {
+        let server = MockServer::start().unwrap();
+        server.add_resource("berry", berry(1, "cheri", "soft", 10));
+
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let client = server.client().unwrap().with_cache(store.clone());
+
+        let cheri = client.berries().get_by_id(1).await.unwrap();
+        let cached = client.berries().get_by_id(1).await.unwrap();
+        assert_eq!(cheri.name, cached.name);
+        assert_eq!(server.requests().len(), 1);
+
+        let url = format!("{}/v2/berry/1", server.base_url());
+        assert!(store.get(&url).is_some());
+
+        let firmness: String = store
+            .connection()
+            .query_row(
+                "SELECT firmness FROM berry WHERE id = 1",
+                rusqlite::NO_PARAMS,
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(firmness, "soft");
+    }
+}
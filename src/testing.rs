@@ -0,0 +1,382 @@
+//! Local HTTP server replaying recorded fixtures, for testing code built on top of `ApiClient`.
+//!
+//! The server listens on `127.0.0.1` and serves resources using the PokeAPI url layout:
+//! `/api/v2/{endpoint}/{id or name}/` for resources and `/api/v2/{endpoint}/?offset=&limit=`
+//! for paginated resource lists. Unknown resources result in `404 Not Found`.
+//! JSON `POST` requests, e.g. GraphQL queries, are answered by handlers added for their paths,
+//! and static files such as sprites can be served at any other path.
+//! Faults such as latency or error statuses can be injected to exercise error handling.
+//!
+//! ```no_run
+//! # async fn example() -> pokemon_api::Result<()> {
+//! use pokemon_api::api::v2::endpoint::ApiEndpoint;
+//! use pokemon_api::testing::MockServer;
+//!
+//! let server = MockServer::start()?;
+//! server.add_resource("berry", serde_json::json!({ "id": 1, "name": "cheri" }));
+//!
+//! let client = server.client()?;
+//! let berries = client.berries().all().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::api::v2::client::ApiClient;
+use crate::http::{self, Request, Response};
+use crate::mirror::read_snapshot;
+use crate::Result;
+
+/// Fault injected into responses of the server.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fault {
+    /// Delays the response.
+    Latency(Duration),
+
+    /// Responds with the given status code instead of the resource.
+    /// `429 Too Many Requests` responses carry `Retry-After: 1` header.
+    Status(u16),
+}
+
+/// Fault with the number of requests it still applies to, `None` meaning every request.
+#[derive(Clone, Debug)]
+struct FaultRule {
+    fault: Fault,
+    remaining: Option<usize>,
+}
+
+/// Resources of a single endpoint, ordered by id.
+type Resources = BTreeMap<i64, Value>;
+
+/// Handler answering the JSON body of a `POST` request.
+type PostHandler = Arc<dyn Fn(&Value) -> Value + Send + Sync>;
+
+#[derive(Default)]
+struct State {
+    endpoints: BTreeMap<String, Resources>,
+    post_handlers: BTreeMap<String, PostHandler>,
+    files: BTreeMap<String, Vec<u8>>,
+    faults: Vec<FaultRule>,
+    requests: Vec<String>,
+}
+
+/// Local HTTP server serving fixtures in the PokeAPI url layout.
+/// The server is stopped when dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl MockServer {
+    /// Starts the server on a free port of `127.0.0.1`.
+    pub fn start() -> Result<MockServer> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::default()));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let server = MockServer {
+            addr,
+            state: state.clone(),
+            stopped: stopped.clone(),
+        };
+        let base = server.base_url();
+        http::serve(
+            listener,
+            stopped,
+            Arc::new(move |request| handle(request, &state, &base)),
+        );
+
+        Ok(server)
+    }
+
+    /// Root url of the served API, to be passed to `ApiClient::with_base_url`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}/api", self.addr)
+    }
+
+    /// Url of the path on this server, e.g. `/graphql/v1beta`.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    /// Creates client requesting this server.
+    pub fn client(&self) -> Result<ApiClient> {
+        Ok(ApiClient::new()?.with_base_url(self.base_url()))
+    }
+
+    /// Adds resource to the endpoint. The resource must have the numeric `id` field
+    /// and is also reachable by its `name` if it has one.
+    /// Urls pointing at `https://pokeapi.co/api/v2` or host relative `/api/v2` are served
+    /// rewritten to point at this server.
+    pub fn add_resource<T: Into<String>>(&self, endpoint: T, resource: Value) {
+        let id = resource["id"].as_i64().unwrap_or_default();
+        self.state
+            .lock()
+            .unwrap()
+            .endpoints
+            .entry(endpoint.into())
+            .or_default()
+            .insert(id, resource);
+    }
+
+    /// Loads every resource found in the directory using the layout of
+    /// [PokeAPI/api-data](https://github.com/PokeAPI/api-data), as written by `Mirror`:
+    /// `{dir}/api/v2/{endpoint}/{id}/index.json`.
+    pub fn load_dir<P: AsRef<Path>>(&self, dir: P) -> Result<usize> {
+        let resources = read_snapshot(dir)?;
+        let loaded = resources.len();
+        for (endpoint, resource) in resources {
+            self.add_resource(endpoint, resource);
+        }
+        Ok(loaded)
+    }
+
+    /// Answers `POST` requests to the path, e.g. `/graphql/v1beta`, with the JSON returned
+    /// by the handler given the JSON body of the request.
+    pub fn add_post_handler<F>(&self, path: &str, handler: F)
+    where
+        F: Fn(&Value) -> Value + Send + Sync + 'static,
+    {
+        self.state
+            .lock()
+            .unwrap()
+            .post_handlers
+            .insert(path.to_string(), Arc::new(handler));
+    }
+
+    /// Serves the file at the path, e.g. `/sprites/pokemon/25.png`.
+    pub fn add_file<B: Into<Vec<u8>>>(&self, path: &str, body: B) {
+        self.state
+            .lock()
+            .unwrap()
+            .files
+            .insert(path.to_string(), body.into());
+    }
+
+    /// Injects the fault into every following response.
+    pub fn add_fault(&self, fault: Fault) {
+        self.state.lock().unwrap().faults.push(FaultRule {
+            fault,
+            remaining: None,
+        });
+    }
+
+    /// Injects the fault into the given number of following responses.
+    pub fn add_fault_times(&self, fault: Fault, times: usize) {
+        self.state.lock().unwrap().faults.push(FaultRule {
+            fault,
+            remaining: Some(times),
+        });
+    }
+
+    /// Removes all injected faults.
+    pub fn clear_faults(&self) {
+        self.state.lock().unwrap().faults.clear();
+    }
+
+    /// Paths with queries of all requests received so far.
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wake up the accepting thread, so it notices the server is stopped.
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+/// Produces response for the request, applying the injected faults.
+fn handle(request: &Request, state: &Mutex<State>, base: &str) -> Response {
+    let (latency, response) = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(request.target.clone());
+
+        let mut latency = Duration::from_millis(0);
+        let mut status = None;
+        for rule in state.faults.iter_mut() {
+            match rule.fault {
+                Fault::Latency(delay) => latency += delay,
+                Fault::Status(code) if status.is_none() => status = Some(code),
+                Fault::Status(_) => continue,
+            }
+            if let Some(ref mut remaining) = rule.remaining {
+                *remaining -= 1;
+            }
+        }
+        state.faults.retain(|rule| rule.remaining != Some(0));
+
+        let response = match status {
+            Some(code) => Response::status(code),
+            None if request.method == "POST" => post(&state, request),
+            None => route(&state, request, base),
+        };
+
+        (latency, response)
+    };
+
+    if latency > Duration::from_millis(0) {
+        thread::sleep(latency);
+    }
+
+    response
+}
+
+/// Produces response for the `POST` request using the handler of its path.
+fn post(state: &State, request: &Request) -> Response {
+    match state.post_handlers.get(request.path()) {
+        Some(handler) => {
+            let body = serde_json::from_slice(&request.body).unwrap_or(Value::Null);
+            Response::json(&handler(&body))
+        }
+        None => Response::status(404),
+    }
+}
+
+/// Produces response for the request target.
+fn route(state: &State, request: &Request, base: &str) -> Response {
+    let path = request.path();
+    if let Some(body) = state.files.get(path) {
+        return Response::file(body);
+    }
+
+    let segments: Vec<&str> = path.split('/').filter(|seg| !seg.is_empty()).collect();
+    match segments.as_slice() {
+        ["api", "v2", endpoint] => match state.endpoints.get(*endpoint) {
+            Some(resources) => {
+                let keys: Vec<(i64, String)> = resources.values().map(http::resource_key).collect();
+                Response::json(&http::page(&keys, endpoint, request.query(), base))
+            }
+            None => Response::status(404),
+        },
+        ["api", "v2", endpoint, key] => {
+            let resource = state.endpoints.get(*endpoint).and_then(|resources| {
+                resources.values().find(|res| {
+                    key.parse::<i64>().ok() == res["id"].as_i64()
+                        || res["name"].as_str() == Some(*key)
+                })
+            });
+
+            match resource {
+                Some(resource) => {
+                    let mut resource = resource.clone();
+                    http::rewrite(&mut resource, base);
+                    Response::json(&resource)
+                }
+                None => Response::status(404),
+            }
+        }
+        _ => Response::status(404),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Fault, MockServer};
+    use crate::api::v2::endpoint::ApiEndpoint;
+    use crate::api::v2::resource::ApiNamedResourceList;
+    use serde_json::json;
+    use std::time::{Duration, Instant};
+
+    fn server() -> MockServer {
+        let server = MockServer::start().unwrap();
+        for (id, name) in ["cheri", "chesto", "pecha", "rawst", "aspear"]
+            .iter()
+            .enumerate()
+        {
+            server.add_resource(
+                "berry-firmness",
+                json!({
+                    "id": id + 1,
+                    "name": name,
+                    "berries": [{ "name": name, "url": format!("https://pokeapi.co/api/v2/berry/{}/", id + 1) }],
+                    "names": [{ "name": name, "language": { "name": "en", "url": "/api/v2/language/9/" } }]
+                }),
+            );
+        }
+        server
+    }
+
+    #[tokio::test]
+    async fn serves_resources() {
+        let server = server();
+        let firmnesses = server.client().unwrap().berry_firmnesses();
+
+        let by_id = firmnesses.get_by_id(2).await.unwrap();
+        assert_eq!(by_id.name, "chesto");
+        assert_eq!(
+            by_id.berries[0].url,
+            format!("{}/v2/berry/2/", server.base_url())
+        );
+        assert_eq!(
+            by_id.names[0].language.url,
+            format!("{}/v2/language/9/", server.base_url())
+        );
+
+        let by_name = firmnesses.get_by_name("rawst").await.unwrap();
+        assert_eq!(by_name.id, 4);
+
+        assert!(firmnesses.get_by_id(42).await.is_err());
+        assert!(firmnesses.get_by_name("unknown").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn paginates_lists() {
+        let server = server();
+        let firmnesses = server.client().unwrap().berry_firmnesses();
+
+        let first = firmnesses.all_paginated(0, 2).await.unwrap();
+        assert_eq!(first.count(), 5);
+        assert!(first.previous_list().await.unwrap().is_none());
+
+        let names: Vec<String> = first.resources().iter().map(|res| res.name()).collect();
+        assert_eq!(names, vec!["cheri", "chesto"]);
+
+        let second = first.next_list().await.unwrap().unwrap();
+        let third = second.next_list().await.unwrap().unwrap();
+        assert_eq!(third.resources().len(), 1);
+        assert!(third.next_list().await.unwrap().is_none());
+        assert!(third.previous_list().await.unwrap().is_some());
+
+        let last = third.resources().remove(0).get().await.unwrap();
+        assert_eq!(last.name, "aspear");
+
+        assert!(server.client().unwrap().berries().all().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn injects_faults() {
+        let server = server();
+        let firmnesses = server.client().unwrap().berry_firmnesses();
+
+        server.add_fault_times(Fault::Status(500), 1);
+        server.add_fault_times(Fault::Status(429), 1);
+
+        let err = firmnesses.get_by_id(1).await.unwrap_err();
+        assert!(err.to_string().contains("500"));
+        let err = firmnesses.get_by_id(1).await.unwrap_err();
+        assert!(err.to_string().contains("429"));
+        assert!(firmnesses.get_by_id(1).await.is_ok());
+
+        server.add_fault(Fault::Latency(Duration::from_millis(100)));
+        let started = Instant::now();
+        assert!(firmnesses.get_by_id(1).await.is_ok());
+        assert!(started.elapsed() >= Duration::from_millis(100));
+
+        server.clear_faults();
+        assert_eq!(server.requests().len(), 4);
+    }
+}
@@ -0,0 +1,518 @@
+//! Offline data source reading the CSV files of [veekun/pokedex](https://github.com/veekun/pokedex).
+//!
+//! PokeAPI is generated from the same files, so the source produces the `models::v2` structs
+//! returned by the API, with references pointing at the configured API url:
+//!
+//! ```no_run
+//! use pokemon_api::veekun::VeekunSource;
+//!
+//! # fn run() -> pokemon_api::Result<()> {
+//! let source = VeekunSource::new("pokedex/pokedex/data/csv");
+//! for berry in source.berries()? {
+//!     println!("{} grows in {} hours", berry.name, berry.growth_time * 4);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Tables only contributing localized names or secondary lists, such as `berry_firmness_names.csv`
+//! or `nature_pokeathlon_stats.csv`, may be missing; the lists are left empty then.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::api::POKE_API_BASE_URL;
+use crate::models::v2::berry::{Berry, BerryFirmness, BerryFlavor, BerryFlavorMap, FlavorBerryMap};
+use crate::models::v2::common::{Id, Integer, Name};
+use crate::models::v2::pokemon::{MoveBattleStylePreference, Nature, NatureStatChange};
+use crate::models::v2::resource::NamedResource;
+use crate::{Error, Result};
+
+/// Identifier of the language flavor names of berry flavors are taken from.
+const FLAVOR_LANGUAGE: &str = "en";
+
+/// Suffix of item identifiers of berries, e.g. `cheri-berry`.
+const BERRY_ITEM_SUFFIX: &str = "-berry";
+
+/// Reads models from the directory of veekun CSV files.
+pub struct VeekunSource {
+    dir: PathBuf,
+    url: String,
+}
+
+impl VeekunSource {
+    /// Creates source reading the CSV files from `dir`.
+    /// References point at the public API, like `https://pokeapi.co/api/v2/berry-firmness/2/`.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: dir.into(),
+            url: format!("{}/v2", POKE_API_BASE_URL),
+        }
+    }
+
+    /// Points references at the API hosted at the given url, like `https://pokeapi.co/api`.
+    pub fn with_base_url<T: Into<String>>(mut self, url: T) -> Self {
+        self.url = url.into().trim_end_matches('/').to_string() + "/v2";
+        self
+    }
+
+    /// All berries, ordered by id.
+    pub fn berries(&self) -> Result<Vec<Berry>> {
+        let items = self.identifiers("items")?;
+        let firmnesses = self.identifiers("berry_firmness")?;
+        let types = self.identifiers("types")?;
+        let flavors = self.flavor_names()?;
+
+        let mut berry_flavors: HashMap<Id, Vec<BerryFlavorMap>> = HashMap::new();
+        for row in self.read("berry_flavors")?.rows() {
+            let flavor = row.int("contest_type_id")?;
+            berry_flavors
+                .entry(row.int("berry_id")?)
+                .or_default()
+                .push(BerryFlavorMap {
+                    potency: row.int("flavor")?,
+                    flavor: self.reference("berry-flavor", flavor, &flavors)?,
+                });
+        }
+
+        let mut berries = Vec::new();
+        for row in self.read("berries")?.rows() {
+            let id = row.int("id")?;
+            let item = self.reference("item", row.int("item_id")?, &items)?;
+            let mut flavors = berry_flavors.remove(&id).unwrap_or_default();
+            flavors.sort_by_key(|f| f.flavor.id());
+
+            berries.push(Berry {
+                id,
+                name: berry_name(&item.name).to_string(),
+                growth_time: row.int("growth_time")?,
+                max_harvest: row.int("max_harvest")?,
+                natural_gift_power: row.int("natural_gift_power")?,
+                size: row.int("size")?,
+                smoothness: row.int("smoothness")?,
+                soil_dryness: row.int("soil_dryness")?,
+                firmness: self.reference("berry-firmness", row.int("firmness_id")?, &firmnesses)?,
+                flavors,
+                item,
+                natural_gift_type: self.reference(
+                    "type",
+                    row.int("natural_gift_type_id")?,
+                    &types,
+                )?,
+            });
+        }
+        berries.sort_by_key(|b| b.id);
+        Ok(berries)
+    }
+
+    /// All berry firmnesses, ordered by id.
+    pub fn berry_firmnesses(&self) -> Result<Vec<BerryFirmness>> {
+        let mut members: HashMap<Id, Vec<NamedResource>> = HashMap::new();
+        for berry in self.berries()? {
+            let firmness = berry.firmness.id().unwrap_or_default();
+            members.entry(firmness).or_default().push(self.resource(
+                "berry",
+                berry.id,
+                &berry.name,
+            ));
+        }
+
+        let mut names = self.names("berry_firmness_names", "berry_firmness_id", "name")?;
+        let mut firmnesses = Vec::new();
+        for (id, name) in self.identifiers("berry_firmness")? {
+            firmnesses.push(BerryFirmness {
+                id,
+                name,
+                berries: members.remove(&id).unwrap_or_default(),
+                names: names.remove(&id).unwrap_or_default(),
+            });
+        }
+        Ok(firmnesses)
+    }
+
+    /// All berry flavors, ordered by id.
+    /// Flavors share their ids with the contest types they correlate with.
+    pub fn berry_flavors(&self) -> Result<Vec<BerryFlavor>> {
+        let mut members: HashMap<Id, Vec<FlavorBerryMap>> = HashMap::new();
+        for berry in self.berries()? {
+            for flavor in berry.flavors.iter() {
+                members
+                    .entry(flavor.flavor.id().unwrap_or_default())
+                    .or_default()
+                    .push(FlavorBerryMap {
+                        potency: flavor.potency,
+                        berry: self.resource("berry", berry.id, &berry.name),
+                    });
+            }
+        }
+
+        let contest_types = self.identifiers("contest_types")?;
+        let mut names = self.names("contest_type_names", "contest_type_id", "flavor")?;
+        let mut flavors = Vec::new();
+        for (id, name) in self.flavor_names()? {
+            flavors.push(BerryFlavor {
+                id,
+                name,
+                berries: members.remove(&id).unwrap_or_default(),
+                contest_type: self.reference("contest-type", id, &contest_types)?,
+                names: names.remove(&id).unwrap_or_default(),
+            });
+        }
+        Ok(flavors)
+    }
+
+    /// All natures, ordered by id.
+    /// Neutral natures, which increase and decrease the same stat, reference no stats or flavors.
+    pub fn natures(&self) -> Result<Vec<Nature>> {
+        let stats = self.identifiers("stats")?;
+        let flavors = self.flavor_names()?;
+
+        let pokeathlon_stats = self.optional_identifiers("pokeathlon_stats")?;
+        let mut stat_changes: HashMap<Id, Vec<NatureStatChange>> = HashMap::new();
+        for row in self.read_optional("nature_pokeathlon_stats")?.rows() {
+            let stat = row.int("pokeathlon_stat_id")?;
+            stat_changes
+                .entry(row.int("nature_id")?)
+                .or_default()
+                .push(NatureStatChange {
+                    max_change: row.int("max_change")?,
+                    pokeathlon_stat: self.reference("pokeathlon-stat", stat, &pokeathlon_stats)?,
+                });
+        }
+
+        let styles = self.optional_identifiers("move_battle_styles")?;
+        let mut preferences: HashMap<Id, Vec<MoveBattleStylePreference>> = HashMap::new();
+        for row in self
+            .read_optional("nature_battle_style_preferences")?
+            .rows()
+        {
+            let style = row.int("move_battle_style_id")?;
+            preferences
+                .entry(row.int("nature_id")?)
+                .or_default()
+                .push(MoveBattleStylePreference {
+                    low_hp_preference: row.int("low_hp_preference")?,
+                    high_hp_preference: row.int("high_hp_preference")?,
+                    move_battle_style: self.reference("move-battle-style", style, &styles)?,
+                });
+        }
+
+        let mut names = self.names("nature_names", "nature_id", "name")?;
+        let mut natures = Vec::new();
+        for row in self.read("natures")?.rows() {
+            let id = row.int("id")?;
+            let (increased, decreased) =
+                (row.int("increased_stat_id")?, row.int("decreased_stat_id")?);
+            let (likes, hates) = (row.int("likes_flavor_id")?, row.int("hates_flavor_id")?);
+            let neutral = increased == decreased;
+            let reference = |endpoint, id, names: &BTreeMap<Id, String>| -> Result<_> {
+                if neutral {
+                    Ok(None)
+                } else {
+                    self.reference(endpoint, id, names).map(Some)
+                }
+            };
+
+            natures.push(Nature {
+                id,
+                name: row.text("identifier")?.to_string(),
+                decreased_stat: reference("stat", decreased, &stats)?,
+                increased_stat: reference("stat", increased, &stats)?,
+                hates_flavor: reference("berry-flavor", hates, &flavors)?,
+                likes_flavor: reference("berry-flavor", likes, &flavors)?,
+                pokeathlon_stat_changes: stat_changes.remove(&id).unwrap_or_default(),
+                move_battle_style_preferences: preferences.remove(&id).unwrap_or_default(),
+                names: names.remove(&id).unwrap_or_default(),
+            });
+        }
+        natures.sort_by_key(|n| n.id);
+        Ok(natures)
+    }
+
+    /// Names of berry flavors by id: the lowercase english flavor names of contest types.
+    fn flavor_names(&self) -> Result<BTreeMap<Id, String>> {
+        Ok(self
+            .names("contest_type_names", "contest_type_id", "flavor")?
+            .into_iter()
+            .filter_map(|(id, names)| {
+                names
+                    .into_iter()
+                    .find(|n| n.language.name == FLAVOR_LANGUAGE)
+                    .map(|n| (id, n.name.to_lowercase()))
+            })
+            .collect())
+    }
+
+    /// Localized names of the table, by id of the named row.
+    fn names(&self, table: &str, key: &str, column: &str) -> Result<HashMap<Id, Vec<Name>>> {
+        let names = self.read_optional(table)?;
+        if names.rows.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let languages = self.identifiers("languages")?;
+        let mut result: HashMap<Id, Vec<Name>> = HashMap::new();
+        for row in names.rows() {
+            result.entry(row.int(key)?).or_default().push(Name {
+                name: row.text(column)?.to_string(),
+                language: self.reference("language", row.int("local_language_id")?, &languages)?,
+            });
+        }
+        Ok(result)
+    }
+
+    /// Identifiers of the table, by id.
+    fn identifiers(&self, table: &str) -> Result<BTreeMap<Id, String>> {
+        identifiers(&self.read(table)?)
+    }
+
+    fn optional_identifiers(&self, table: &str) -> Result<BTreeMap<Id, String>> {
+        identifiers(&self.read_optional(table)?)
+    }
+
+    fn reference(
+        &self,
+        endpoint: &str,
+        id: Id,
+        names: &BTreeMap<Id, String>,
+    ) -> Result<NamedResource> {
+        let name = names.get(&id).ok_or_else(|| {
+            Error::InvalidArgument(format!("Unknown {} with id {}", endpoint, id))
+        })?;
+        Ok(self.resource(endpoint, id, name))
+    }
+
+    fn resource(&self, endpoint: &str, id: Id, name: &str) -> NamedResource {
+        NamedResource {
+            name: name.to_string(),
+            url: format!("{}/{}/{}/", self.url, endpoint, id),
+        }
+    }
+
+    fn read(&self, table: &str) -> Result<CsvTable> {
+        let path = self.dir.join(format!("{}.csv", table));
+        CsvTable::parse(table, &fs::read_to_string(path)?)
+    }
+
+    fn read_optional(&self, table: &str) -> Result<CsvTable> {
+        match self.read(table) {
+            Err(Error::IoError(e)) if e.kind() == io::ErrorKind::NotFound => Ok(CsvTable {
+                name: table.to_string(),
+                columns: HashMap::new(),
+                rows: Vec::new(),
+            }),
+            other => other,
+        }
+    }
+}
+
+fn identifiers(table: &CsvTable) -> Result<BTreeMap<Id, String>> {
+    table
+        .rows()
+        .map(|row| Ok((row.int("id")?, row.text("identifier")?.to_string())))
+        .collect()
+}
+
+/// Name of the berry of the item, e.g. `cheri` for `cheri-berry`.
+fn berry_name(item: &str) -> &str {
+    item.strip_suffix(BERRY_ITEM_SUFFIX).unwrap_or(item)
+}
+
+/// Parsed CSV file with a header row.
+struct CsvTable {
+    name: String,
+    columns: HashMap<String, usize>,
+    rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    fn parse(name: &str, text: &str) -> Result<Self> {
+        let mut records = parse_csv(text)
+            .map_err(|e| Error::InvalidArgument(format!("{}.csv: {}", name, e)))?
+            .into_iter();
+        let columns = records
+            .next()
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(index, column)| (column, index))
+            .collect();
+
+        Ok(Self {
+            name: name.to_string(),
+            columns,
+            rows: records.collect(),
+        })
+    }
+
+    fn rows(&self) -> impl Iterator<Item = CsvRow<'_>> {
+        self.rows.iter().map(move |fields| CsvRow {
+            table: self,
+            fields,
+        })
+    }
+}
+
+struct CsvRow<'a> {
+    table: &'a CsvTable,
+    fields: &'a [String],
+}
+
+impl<'a> CsvRow<'a> {
+    fn text(&self, column: &str) -> Result<&'a str> {
+        self.table
+            .columns
+            .get(column)
+            .and_then(|&index| self.fields.get(index))
+            .map(String::as_str)
+            .ok_or_else(|| {
+                Error::InvalidArgument(format!(
+                    "{}.csv: missing column {}",
+                    self.table.name, column
+                ))
+            })
+    }
+
+    fn int(&self, column: &str) -> Result<Integer> {
+        let text = self.text(column)?;
+        text.parse().map_err(|_| {
+            Error::InvalidArgument(format!(
+                "{}.csv: invalid {} value '{}'",
+                self.table.name, column, text
+            ))
+        })
+    }
+}
+
+/// Splits RFC 4180 text into records, skipping empty lines.
+fn parse_csv(text: &str) -> std::result::Result<Vec<Vec<String>>, &'static str> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                if record.len() > 1 || !record[0].is_empty() {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            (false, c) => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("unterminated quoted field");
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_csv, VeekunSource};
+    use crate::models::v2::common::HasNames;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn write_tables(dir: &PathBuf) {
+        let tables = [
+            ("languages", "id,iso639,iso3166,identifier,official,order\n5,fr,fr,fr,1,5\n9,en,us,en,1,7\n"),
+            ("items", "id,identifier,category_id,cost,fling_power,fling_effect_id\n126,cheri-berry,3,20,10,3\n127,chesto-berry,3,20,10,3\n"),
+            ("types", "id,identifier,generation_id,damage_class_id\n10,fire,1,3\n11,water,1,3\n"),
+            ("berry_firmness", "id,identifier\n2,soft\n5,super-hard\n"),
+            ("berry_firmness_names", "berry_firmness_id,local_language_id,name\n2,9,Soft\n2,5,Tendre\n"),
+            ("berries", "id,item_id,firmness_id,natural_gift_power,natural_gift_type_id,size,max_harvest,growth_time,soil_dryness,smoothness\n2,127,5,60,11,80,5,3,15,25\n1,126,2,60,10,20,5,3,15,25\n"),
+            ("contest_types", "id,identifier\n1,cool\n2,beauty\n"),
+            ("contest_type_names", "contest_type_id,local_language_id,name,flavor,color\n1,9,Cool,Spicy,Red\n1,5,Sang-froid,\"Épicé, fort\",Rouge\n2,9,Beauty,Dry,Blue\n"),
+            ("berry_flavors", "berry_id,contest_type_id,flavor\n1,2,0\n1,1,10\n2,1,0\n2,2,10\n"),
+            ("stats", "id,damage_class_id,identifier,is_battle_only,game_index\n2,2,attack,0,1\n3,2,defense,0,2\n"),
+            ("natures", "id,identifier,decreased_stat_id,increased_stat_id,hates_flavor_id,likes_flavor_id,game_index\n1,hardy,2,2,1,1,0\n6,lonely,3,2,2,1,1\n"),
+        ];
+
+        fs::create_dir_all(dir).unwrap();
+        for (table, text) in tables.iter() {
+            fs::write(dir.join(format!("{}.csv", table)), text).unwrap();
+        }
+    }
+
+    #[test]
+    fn parses_quoted_fields() {
+        let records = parse_csv("a,b\r\n\"x, \"\"y\"\"\",\n\n1,\"2\n3\"").unwrap();
+        assert_eq!(
+            records,
+            vec![vec!["a", "b"], vec!["x, \"y\"", ""], vec!["1", "2\n3"],]
+        );
+        assert!(parse_csv("a,\"b").is_err());
+    }
+
+    #[test]
+    fn reads_models() {
+        let dir = env::temp_dir().join(format!("pokemon-api-veekun-{}", std::process::id()));
+        write_tables(&dir);
+        let source = VeekunSource::new(&dir);
+
+        let berries = source.berries().unwrap();
+        let names: Vec<&str> = berries.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["cheri", "chesto"]);
+        let cheri = &berries[0];
+        assert_eq!(cheri.firmness.name, "soft");
+        assert_eq!(
+            cheri.firmness.url,
+            "https://pokeapi.co/api/v2/berry-firmness/2/"
+        );
+        assert_eq!(cheri.item.name, "cheri-berry");
+        assert_eq!(cheri.natural_gift_type.name, "fire");
+        assert_eq!(cheri.flavors[0].flavor.name, "spicy");
+        assert_eq!(cheri.flavors[0].potency, 10);
+
+        let firmnesses = source.berry_firmnesses().unwrap();
+        assert_eq!(firmnesses[0].berries[0].name, "cheri");
+        assert_eq!(firmnesses[0].localized_name(&["fr"]), Some("Tendre"));
+        assert!(firmnesses[1].names.is_empty());
+
+        let flavors = source.berry_flavors().unwrap();
+        assert_eq!(flavors[1].name, "dry");
+        assert_eq!(flavors[1].contest_type.name, "beauty");
+        assert_eq!(flavors[1].berries[1].berry.name, "chesto");
+        assert_eq!(flavors[1].berries[1].potency, 10);
+        assert_eq!(flavors[0].localized_name(&["fr"]), Some("Épicé, fort"));
+
+        let natures = source
+            .with_base_url("http://localhost:8000/api/")
+            .natures()
+            .unwrap();
+        assert!(natures[0].increased_stat.is_none());
+        assert!(natures[0].likes_flavor.is_none());
+        let lonely = &natures[1];
+        assert_eq!(lonely.increased_stat.as_ref().unwrap().name, "attack");
+        assert_eq!(lonely.decreased_stat.as_ref().unwrap().name, "defense");
+        assert_eq!(lonely.likes_flavor.as_ref().unwrap().name, "spicy");
+        assert_eq!(
+            lonely.hates_flavor.as_ref().unwrap().url,
+            "http://localhost:8000/api/v2/berry-flavor/2/"
+        );
+        assert!(lonely.pokeathlon_stat_changes.is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}